@@ -0,0 +1,65 @@
+// compares AVLTree, Treap and SplayTree on the same sequence workloads.
+// run with `cargo bench --bench sequence`
+#[macro_use]
+extern crate cp_rust_library;
+
+use cp_rust_library::algebra::*;
+use cp_rust_library::data_structures::node_traits::*;
+use cp_rust_library::data_structures::avl_tree::avl_tree_array::AVLTree;
+use cp_rust_library::data_structures::treap::treap_array::Treap;
+use cp_rust_library::data_structures::splay_tree::splay_tree_array::SplayTree;
+use cp_rust_library::random::{ Random, Xorshift128 };
+
+use std::time::Instant;
+
+monoid! { Sum, (i64, i64), (0, 0), |a, b| (a.0 + b.0, a.1 + b.1) }
+monoid! { Add, i64, 0, |a, b| a + b }
+action! { Add => Sum, |x, f| (x.0 + f * x.1, x.1) }
+
+impl Reverse for Sum {
+    fn reverse(&self) -> Self { self.clone() }
+}
+
+def_node! { AVLNode, Sum; size, height, fold, rev, lazy(Add), }
+def_node! { TreapNode, Sum; size, priority, fold, rev, lazy(Add), }
+def_node! { SplayNode, Sum; size, fold, rev, lazy(Add), }
+
+const N: usize = 200_000;
+const Q: usize = 200_000;
+
+macro_rules! bench_tree {
+    ($name:expr, $tree:ident, $node:ident) => {{
+        let mut rng = Xorshift128::new(1);
+        let start = Instant::now();
+        let mut arr = $tree::<$node>::from_vec((0..N as i64).map(|x| Sum((x, 1))).collect());
+        let mut check = 0i64;
+        for _ in 0..Q {
+            let n = arr.size();
+            let l = (rng.rand_u64() % (n as u64 + 1)) as usize;
+            let r = l + (rng.rand_u64() % ((n - l) as u64 + 1)) as usize;
+            let x = (rng.rand_u64() % 100) as i64;
+            match rng.rand_u64() % 6 {
+                0 => arr.insert(l, Sum((x, 1))),
+                1 if l < n => check ^= arr.erase(l).0.0,
+                2 => arr.update(l..r, Add(x)),
+                3 => arr.reverse_range(l..r),
+                4 if l < n => check ^= arr.at(Position(l)).unwrap().0.0,
+                _ => check ^= arr.fold(l..r).0.0,
+            }
+        }
+        let random = start.elapsed();
+        let start = Instant::now();
+        for i in 0..arr.size() {
+            check ^= arr.at(Position(i)).unwrap().0.0;
+        }
+        check ^= arr.into_vec().len() as i64;
+        let sequential = start.elapsed();
+        println!("{:<10} random: {:>8.1?}  sequential: {:>8.1?}  ({})", $name, random, sequential, check);
+    }};
+}
+
+fn main() {
+    bench_tree!("AVLTree", AVLTree, AVLNode);
+    bench_tree!("Treap", Treap, TreapNode);
+    bench_tree!("SplayTree", SplayTree, SplayNode);
+}
@@ -18,6 +18,10 @@ pub trait Inv: Magma {
     fn inv(&self) -> Self;
 }
 
+pub trait Commutative: Magma {}
+
+pub trait Idempotent: Magma {}
+
 pub trait Effect<E: Monoid> {
     fn effect(&self, e: &E) -> Self;
 }
@@ -39,3 +43,106 @@ pub trait Field:
     fn zero() -> Self;
     fn one() -> Self;
 }
+
+#[macro_export]
+macro_rules! monoid {
+    ($(#[$m:meta])* $vis:vis $name:ident, $t:ty, $id:expr, |$a:ident, $b:ident| $op:expr) => {
+        $(#[$m])*
+        #[derive(Clone)]
+        $vis struct $name(pub $t);
+        impl $crate::algebra::Magma for $name {
+            #[allow(unused_variables)]
+            fn op(&self, rhs: &Self) -> Self {
+                let $a: $t = self.0.clone();
+                let $b: $t = rhs.0.clone();
+                $name($op)
+            }
+        }
+        impl $crate::algebra::Associative for $name {}
+        impl $crate::algebra::Unital for $name {
+            fn identity() -> Self { $name($id) }
+        }
+    };
+    ($(#[$m:meta])* $vis:vis $name:ident, $t:ty, $id:expr, |$a:ident, $b:ident| $op:expr, ctx $c:ident: $ct:ty) => {
+        $(#[$m])*
+        #[derive(Clone)]
+        $vis struct $name(pub $t);
+        impl $name {
+            thread_local! {
+                static CONTEXT: std::cell::RefCell<Option<$ct>> = std::cell::RefCell::new(None);
+            }
+            #[allow(dead_code)]
+            pub fn set_context(c: $ct) {
+                Self::CONTEXT.with(|ctx| *ctx.borrow_mut() = Some(c));
+            }
+            #[allow(dead_code)]
+            pub fn context() -> $ct {
+                Self::CONTEXT.with(|ctx| ctx.borrow().clone().expect(concat!("context of ", stringify!($name), " is not set")))
+            }
+        }
+        impl $crate::algebra::Magma for $name {
+            #[allow(unused_variables)]
+            fn op(&self, rhs: &Self) -> Self {
+                let $c: $ct = Self::context();
+                let $a: $t = self.0.clone();
+                let $b: $t = rhs.0.clone();
+                $name($op)
+            }
+        }
+        impl $crate::algebra::Associative for $name {}
+        impl $crate::algebra::Unital for $name {
+            #[allow(unused_variables)]
+            fn identity() -> Self {
+                let $c: $ct = Self::context();
+                $name($id)
+            }
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! action {
+    ($e:ident => $t:ident, |$x:ident, $f:ident| $eff:expr) => {
+        impl $crate::algebra::Effect<$e> for $t {
+            #[allow(unused_variables)]
+            fn effect(&self, e: &$e) -> Self {
+                let $x = self.0.clone();
+                let $f = e.0.clone();
+                $t($eff)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod algebra_macro_test {
+    use crate::algebra::*;
+    use crate::data_structures::segment_tree::segment_tree::SegmentTree;
+    use crate::data_structures::segment_tree::lazy_segment_tree::LazySegmentTree;
+
+    monoid! { #[derive(Debug)] Sum, (i64, i64), (0, 0), |a, b| (a.0 + b.0, a.1 + b.1) }
+    monoid! { Add, i64, 0, |a, b| a + b }
+    monoid! { Min, i64, i64::MAX, |a, b| std::cmp::min(a, b) }
+    action! { Add => Sum, |x, f| (x.0 + f * x.1, x.1) }
+
+    monoid! { AffineMod, (u64, u64), (1, 0), |f, g| (f.0 * g.0 % m, (f.1 * g.0 + g.1) % m), ctx m: u64 }
+
+    #[test]
+    fn monoid_macro_test() {
+        let seg = SegmentTree::init(&[Min(3), Min(1), Min(4), Min(1), Min(5)]);
+        assert_eq!(seg.fold(0..1).0, 3);
+        assert_eq!(seg.fold(2..5).0, 1);
+        assert_eq!(seg.fold(2..3).0, 4);
+
+        let mut seg = LazySegmentTree::new(&vec![Sum((0, 1)); 5]);
+        seg.update(1..4, Add(2));
+        seg.update(0..2, Add(1));
+        assert_eq!(seg.fold(..).0, (8, 5));
+        assert_eq!(seg.fold(1..2).0, (3, 1));
+
+        AffineMod::set_context(7);
+        let seg = SegmentTree::init(&[AffineMod((2, 3)), AffineMod((3, 4)), AffineMod((5, 6))]);
+        assert_eq!(seg.fold(0..3).0, (2, 1));
+        assert_eq!(AffineMod::identity().0, (1, 0));
+    }
+}
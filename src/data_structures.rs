@@ -1,8 +1,18 @@
 pub mod segment_tree;
+pub mod fenwick_tree;
+pub mod wavelet_matrix;
+pub mod sparse_table;
 pub mod containers;
 pub mod heap;
 pub mod tree;
+#[macro_use]
+pub mod node_macro;
+pub mod node_traits;
+#[macro_use]
+pub mod node_seq;
 pub mod avl_tree;
+pub mod treap;
+pub mod splay_tree;
 pub mod implicit_avl_tree;
 pub mod union_find;
 pub mod set;
@@ -1,4 +1,4 @@
-#[macro_use]
-pub mod node_macro;
-pub mod node_traits;
 pub mod avl_tree_array;
+pub mod avl_map;
+pub mod avl_set;
+pub mod persistent_avl_tree;
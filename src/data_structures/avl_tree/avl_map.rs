@@ -0,0 +1,291 @@
+use crate::data_structures::node_traits::*;
+use crate::data_structures::avl_tree::avl_tree_array::{ AVLTree, AVLNode };
+use crate::algebra::*;
+
+use std::ops::{ Bound, RangeBounds, RangeFull };
+
+pub trait KeyNode: AVLNode + SizeNode {
+    type Key: Ord;
+    fn new(key: Self::Key, val: Self::Value) -> Self;
+    fn key(&self) -> &Self::Key;
+    fn into_pair(self) -> (Self::Key, Self::Value);
+}
+
+fn child<N: Node>(node: &N, dir: usize) -> Option<&N> {
+    node.child(dir).as_ref().map(|ch| &**ch)
+}
+
+def_node! { pub MapNode<K: Ord, V>, key: K, V; size, height, }
+
+impl<K: Ord, V> KeyNode for MapNode<K, V> {
+    type Key = K;
+    fn new(key: K, val: V) -> Self { MapNode::new(key, val) }
+    fn key(&self) -> &K { &self.key }
+    fn into_pair(self) -> (K, V) { (self.key, self.val) }
+}
+
+def_node! { pub FoldMapNode<K: Ord, V: Monoid>, key: K, V; size, height, fold, }
+
+impl<K: Ord, V: Monoid> KeyNode for FoldMapNode<K, V> {
+    type Key = K;
+    fn new(key: K, val: V) -> Self { FoldMapNode::new(key, val) }
+    fn key(&self) -> &K { &self.key }
+    fn into_pair(self) -> (K, V) { (self.key, self.val) }
+}
+
+fn satisfies_start<K: Ord>(key: &K, bound: Bound<&K>) -> bool {
+    match bound {
+        Bound::Included(s) => s <= key,
+        Bound::Excluded(s) => s < key,
+        Bound::Unbounded => true,
+    }
+}
+
+fn satisfies_end<K: Ord>(key: &K, bound: Bound<&K>) -> bool {
+    match bound {
+        Bound::Included(e) => key <= e,
+        Bound::Excluded(e) => key < e,
+        Bound::Unbounded => true,
+    }
+}
+
+pub struct AVLMapBase<N: KeyNode> {
+    tree: AVLTree<N>,
+}
+
+pub type AVLMap<K, V> = AVLMapBase<MapNode<K, V>>;
+pub type AVLFoldMap<K, V> = AVLMapBase<FoldMapNode<K, V>>;
+
+impl<N: KeyNode> Default for AVLMapBase<N> where for<'a> N: KeySearch<&'a N::Key> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<N: KeyNode> AVLMapBase<N> where for<'a> N: KeySearch<&'a N::Key> {
+    pub fn new() -> Self {
+        AVLMapBase { tree: AVLTree::empty() }
+    }
+
+    pub fn len(&self) -> usize { self.tree.size() }
+
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    pub fn get(&self, key: &N::Key) -> Option<&N::Value> {
+        let mut x = self.tree.root();
+        while let Some(node) = x {
+            x = match node.key().cmp(key) {
+                std::cmp::Ordering::Greater => child(node, 0),
+                std::cmp::Ordering::Equal => return Some(node.value()),
+                std::cmp::Ordering::Less => child(node, 1),
+            };
+        }
+        None
+    }
+
+    pub fn contains_key(&self, key: &N::Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    // splits off the node with `key` if it exists
+    fn split3(&mut self, key: &N::Key) -> (AVLTree<N>, Option<Box<N>>, AVLTree<N>) {
+        let tree = std::mem::replace(&mut self.tree, AVLTree::empty());
+        let (l, r) = tree.split(key);
+        let mut first = r.root();
+        while let Some(ch) = first.and_then(|node| child(node, 0)) { first = Some(ch); }
+        match first {
+            Some(node) if node.key() == key => {
+                let (m, r) = r.split::<Position>(Position(1));
+                (l, m.into_root(), r)
+            }
+            _ => (l, None, r),
+        }
+    }
+
+    pub fn insert(&mut self, key: N::Key, val: N::Value) -> Option<N::Value> {
+        let (l, m, r) = self.split3(&key);
+        self.tree = l.merge(AVLTree::new(N::new(key, val))).merge(r);
+        m.map(|m| m.into_pair().1)
+    }
+
+    pub fn remove(&mut self, key: &N::Key) -> Option<N::Value> {
+        let (l, m, r) = self.split3(key);
+        self.tree = l.merge(r);
+        m.map(|m| m.into_pair().1)
+    }
+
+    // the first node whose key satisfies `pred`, which must be monotone
+    fn partition_point<F: Fn(&N::Key) -> bool>(&self, pred: F) -> Option<&N> {
+        let mut x = self.tree.root();
+        let mut ans = None;
+        while let Some(node) = x {
+            if pred(node.key()) {
+                ans = Some(node);
+                x = child(node, 0);
+            }
+            else { x = child(node, 1); }
+        }
+        ans
+    }
+
+    // the first entry whose key is not less than `key`
+    pub fn lower_bound(&self, key: &N::Key) -> Option<(&N::Key, &N::Value)> {
+        self.partition_point(|k| key <= k).map(|node| (node.key(), node.value()))
+    }
+
+    // the first entry whose key is greater than `key`
+    pub fn upper_bound(&self, key: &N::Key) -> Option<(&N::Key, &N::Value)> {
+        self.partition_point(|k| key < k).map(|node| (node.key(), node.value()))
+    }
+
+    // the number of keys less than `key`
+    pub fn rank(&self, key: &N::Key) -> usize {
+        let mut x = self.tree.root();
+        let mut cnt = 0;
+        while let Some(node) = x {
+            if node.key() < key {
+                cnt += node.child(0).size() + 1;
+                x = child(node, 1);
+            }
+            else { x = child(node, 0); }
+        }
+        cnt
+    }
+
+    pub fn nth(&self, mut k: usize) -> Option<(&N::Key, &N::Value)> {
+        let mut x = self.tree.root();
+        while let Some(node) = x {
+            let ls = node.child(0).size();
+            match ls.cmp(&k) {
+                std::cmp::Ordering::Greater => x = child(node, 0),
+                std::cmp::Ordering::Equal => return Some((node.key(), node.value())),
+                std::cmp::Ordering::Less => {
+                    k -= ls + 1;
+                    x = child(node, 1);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn range<R: RangeBounds<N::Key>>(&self, range: R) -> Range<'_, N, R> {
+        let mut stack = Vec::new();
+        let mut x = self.tree.root();
+        while let Some(node) = x {
+            if satisfies_start(node.key(), range.start_bound()) {
+                stack.push(node);
+                x = child(node, 0);
+            }
+            else { x = child(node, 1); }
+        }
+        Range { stack, range }
+    }
+
+    pub fn iter(&self) -> Range<'_, N, RangeFull> {
+        self.range(..)
+    }
+}
+
+impl<N: KeyNode + FoldNode> AVLMapBase<N> where for<'a> N: KeySearch<&'a N::Key>, N::Value: Monoid {
+    fn fold_rec<R: RangeBounds<N::Key>>(x: Option<&N>, range: &R, free: (bool, bool)) -> N::Value {
+        let node = match x {
+            Some(node) => node,
+            None => return N::Value::identity(),
+        };
+        if free.0 && free.1 { return node.fold() }
+        if !free.0 && !satisfies_start(node.key(), range.start_bound()) {
+            Self::fold_rec(child(node, 1), range, free)
+        }
+        else if !free.1 && !satisfies_end(node.key(), range.end_bound()) {
+            Self::fold_rec(child(node, 0), range, free)
+        }
+        else {
+            Self::fold_rec(child(node, 0), range, (free.0, true))
+                .op(node.value())
+                .op(&Self::fold_rec(child(node, 1), range, (true, free.1)))
+        }
+    }
+
+    pub fn fold<R: RangeBounds<N::Key>>(&self, range: R) -> N::Value {
+        Self::fold_rec(self.tree.root(), &range, (false, false))
+    }
+}
+
+pub struct Range<'a, N: KeyNode, R> {
+    stack: Vec<&'a N>,
+    range: R,
+}
+
+impl<'a, N: KeyNode, R: RangeBounds<N::Key>> Iterator for Range<'a, N, R> {
+    type Item = (&'a N::Key, &'a N::Value);
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        if !satisfies_end(node.key(), self.range.end_bound()) {
+            self.stack.clear();
+            return None
+        }
+        let mut x = child(node, 1);
+        while let Some(ch) = x {
+            self.stack.push(ch);
+            x = child(ch, 0);
+        }
+        Some((node.key(), node.value()))
+    }
+}
+
+#[cfg(test)]
+mod avl_map_test {
+    use crate::data_structures::avl_tree::avl_map::{ AVLMap, AVLFoldMap };
+    use crate::algebra::*;
+    use crate::random::{ Random, Xorshift128 };
+    use std::collections::BTreeMap;
+
+    #[derive(Clone, PartialEq, Debug)]
+    struct Am(i64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(41);
+        let mut map = AVLMap::new();
+        let mut fmap = AVLFoldMap::new();
+        let mut std_map = BTreeMap::new();
+        for _ in 0..3000 {
+            let k = (rng.rand_u64() % 200) as i64;
+            let v = (rng.rand_u64() % 100) as i64;
+            match rng.rand_u64() % 5 {
+                0 | 1 => {
+                    assert_eq!(map.insert(k, v), std_map.insert(k, v));
+                    fmap.insert(k, Am(v));
+                }
+                2 => {
+                    assert_eq!(map.remove(&k), std_map.remove(&k));
+                    fmap.remove(&k);
+                }
+                3 => {
+                    assert_eq!(map.get(&k), std_map.get(&k));
+                    assert_eq!(map.lower_bound(&k), std_map.range(k..).next());
+                    assert_eq!(map.upper_bound(&k), std_map.range(k + 1..).next());
+                    assert_eq!(map.rank(&k), std_map.range(..k).count());
+                    let i = (rng.rand_u64() % (std_map.len() as u64 + 1)) as usize;
+                    assert_eq!(map.nth(i), std_map.iter().nth(i));
+                }
+                _ => {
+                    let l = k - 20;
+                    assert!(map.range(l..=k).eq(std_map.range(l..=k)));
+                    assert!(map.range(l..k).eq(std_map.range(l..k)));
+                    assert_eq!(fmap.fold(l..k).0, std_map.range(l..k).map(|e| e.1).sum::<i64>());
+                    assert_eq!(fmap.fold(k..).0, std_map.range(k..).map(|e| e.1).sum::<i64>());
+                }
+            }
+            assert_eq!(map.len(), std_map.len());
+        }
+        assert!(map.iter().eq(std_map.iter()));
+        assert_eq!(fmap.fold(..).0, std_map.values().sum::<i64>());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::data_structures::avl_tree::avl_map::{ AVLMap, MapNode, Range };
+
+use std::ops::{ RangeBounds, RangeFull };
+
+pub struct AVLSet<K: Ord> {
+    map: AVLMap<K, ()>,
+}
+
+impl<K: Ord> Default for AVLSet<K> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<K: Ord> AVLSet<K> {
+    pub fn new() -> Self {
+        AVLSet { map: AVLMap::new() }
+    }
+
+    pub fn len(&self) -> usize { self.map.len() }
+
+    pub fn is_empty(&self) -> bool { self.map.is_empty() }
+
+    pub fn contains(&self, key: &K) -> bool { self.map.contains_key(key) }
+
+    // returns false if the key was already present
+    pub fn insert(&mut self, key: K) -> bool { self.map.insert(key, ()).is_none() }
+
+    pub fn remove(&mut self, key: &K) -> bool { self.map.remove(key).is_some() }
+
+    pub fn lower_bound(&self, key: &K) -> Option<&K> { self.map.lower_bound(key).map(|e| e.0) }
+
+    pub fn upper_bound(&self, key: &K) -> Option<&K> { self.map.upper_bound(key).map(|e| e.0) }
+
+    pub fn rank(&self, key: &K) -> usize { self.map.rank(key) }
+
+    pub fn nth(&self, k: usize) -> Option<&K> { self.map.nth(k).map(|e| e.0) }
+
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Keys<'_, K, R> {
+        Keys { iter: self.map.range(range) }
+    }
+
+    pub fn iter(&self) -> Keys<'_, K, RangeFull> {
+        self.range(..)
+    }
+}
+
+pub struct Keys<'a, K: Ord, R> {
+    iter: Range<'a, MapNode<K, ()>, R>,
+}
+
+impl<'a, K: Ord, R: RangeBounds<K>> Iterator for Keys<'a, K, R> {
+    type Item = &'a K;
+    fn next(&mut self) -> Option<&'a K> { self.iter.next().map(|e| e.0) }
+}
+
+#[cfg(test)]
+mod avl_set_test {
+    use crate::data_structures::avl_tree::avl_set::AVLSet;
+
+    #[test]
+    fn order_statistics_test() {
+        let mut set = AVLSet::new();
+        for &x in [5, 1, 9, 3, 7, 3].iter() { set.insert(x); }
+        assert_eq!(set.len(), 5);
+        assert!(!set.insert(7));
+        assert!(set.contains(&9));
+        assert_eq!(set.lower_bound(&4), Some(&5));
+        assert_eq!(set.upper_bound(&5), Some(&7));
+        assert_eq!(set.upper_bound(&9), None);
+        assert_eq!(set.rank(&6), 3);
+        assert_eq!(set.nth(1), Some(&3));
+        assert!(set.remove(&3));
+        assert!(!set.remove(&3));
+        assert_eq!(set.nth(1), Some(&5));
+        assert_eq!(set.range(2..9).cloned().collect::<Vec<_>>(), vec![5, 7]);
+        assert_eq!(set.iter().cloned().collect::<Vec<_>>(), vec![1, 5, 7, 9]);
+    }
+}
@@ -1,8 +1,11 @@
-use crate::data_structures::avl_tree::node_traits::*;
+use crate::data_structures::node_traits::*;
 use crate::algebra::*;
 
 use std::cmp::Ordering::Greater;
 
+use crate::data_structures::node_seq::{ at, build };
+pub use crate::data_structures::node_seq::Iter;
+
 pub trait AVLNode: Node + HeightNode {}
 impl<N: Node + HeightNode> AVLNode for N {}
 
@@ -141,19 +144,6 @@ fn split<K, N: AVLNode + KeySearch<K>>(mut x: Box<N>, mut pos: K) -> (Link<N>, L
     (l, r)
 }
 
-fn at<K, N: AVLNode + KeySearch<K>>(mut x: &mut Box<N>, mut pos: K) -> Option<&N::Value> {
-    x.push();
-    while let Some((dir, np)) = x.key_search(pos) {
-        pos = np;
-        x = match x.child_mut(dir).as_mut() {
-            Some(x) => x,
-            None => return None,
-        };
-        x.push();
-    }
-    Some(x.value())
-}
-
 fn set<K, N: AVLNode + KeySearch<K>>(mut x: Box<N>, mut pos: K, val: N::Value) -> Box<N> {
     let mut par = Link::Dummy;
     x.push();
@@ -206,41 +196,36 @@ impl<N: AVLNode> AVLTree<N> {
     }
     pub fn at<K>(&mut self, pos: K) -> Option<&N::Value>
     where N: KeySearch<K> {
-        at(self.root.as_mut().unwrap(), pos)
+        at(self.root.as_mut()?, pos)
     }
     pub fn set<K>(&mut self, pos: K, val: N::Value)
     where N: KeySearch<K> {
-        self.root = Link::Some(set(self.root.take().unwrap(), pos, val));
-    }
-}
-
-impl<N: AVLNode + SizeNode> AVLTree<N> {
-    pub fn size(&self) -> usize {
-        self.root.size()
+        let root = match self.root.take() {
+            Link::Some(root) => root,
+            _ => panic!("set on an empty tree"),
+        };
+        self.root = Link::Some(set(root, pos, val));
     }
-}
-
-impl<N: AVLNode + ReversibleNode> AVLTree<N> {
-    pub fn reverse(&mut self) {
-        if let Link::Some(ref mut r) = self.root {
-            r.reverse()
-        }
+    pub fn root(&self) -> Option<&N> {
+        self.root.as_ref().map(|r| &**r)
     }
-}
-
-impl<N: AVLNode + FoldNode> AVLTree<N> where <N as Node>::Value: Monoid {
-    pub fn fold(&self) -> N::Value {
+    pub fn into_root(self) -> Option<Box<N>> {
         match self.root {
-            Link::Some(ref node) => node.fold(),
-            _ => <N as Node>::Value::identity(),
+            Link::Some(r) => Some(r),
+            _ => None,
         }
     }
+    pub fn from_vec(vec: Vec<N::Value>) -> Self where N: ValueNode {
+        let n = vec.len();
+        AVLTree { root: build(&mut vec.into_iter(), n) }
+    }
 }
 
+impl_sequence! { AVLTree, AVLNode }
 
 #[cfg(test)]
 mod avlarray_normal_test {
-    use crate::data_structures::avl_tree::node_traits::*;
+    use crate::data_structures::node_traits::*;
     use crate::data_structures::avl_tree::avl_tree_array::AVLTree;
 
     struct M(usize);
@@ -270,7 +255,7 @@ mod avlarray_normal_test {
 
 #[cfg(test)]
 mod avlarray_reverse_test {
-    use crate::data_structures::avl_tree::node_traits::*;
+    use crate::data_structures::node_traits::*;
     use crate::data_structures::avl_tree::avl_tree_array::AVLTree;
 
     struct M(usize);
@@ -296,7 +281,7 @@ mod avlarray_reverse_test {
 
 #[cfg(test)]
 mod avlarray_fold_test {
-    use crate::data_structures::avl_tree::node_traits::*;
+    use crate::data_structures::node_traits::*;
     use crate::data_structures::avl_tree::avl_tree_array::AVLTree;
     use crate::algebra::*;
 
@@ -324,19 +309,19 @@ mod avlarray_fold_test {
         }
 
         let (arr, _) = arr.split(Position(10));
-        assert_eq!(arr.fold().0, (0..10).sum());
+        assert_eq!(arr.all_fold().0, (0..10).sum());
         let (l, r) = arr.split(Position(4));
         assert_eq!(l.size(), 4);
         assert_eq!(r.size(), 6);
-        assert_eq!(l.fold().0, (0..4).sum());
-        assert_eq!(r.fold().0, (4..10).sum());
+        assert_eq!(l.all_fold().0, (0..4).sum());
+        assert_eq!(r.all_fold().0, (4..10).sum());
     }
 }
 
 
 #[cfg(test)]
 mod avlmap_fold_test {
-    use crate::data_structures::avl_tree::node_traits::*;
+    use crate::data_structures::node_traits::*;
     use crate::data_structures::avl_tree::avl_tree_array::AVLTree;
     use crate::algebra::*;
 
@@ -364,11 +349,71 @@ mod avlmap_fold_test {
         }
 
         let (arr, _) = arr.split(&20);
-        assert_eq!(arr.fold().0, (0..10).sum());
+        assert_eq!(arr.all_fold().0, (0..10).sum());
         let (l, r) = arr.split(&7);
         assert_eq!(l.size(), 4);
         assert_eq!(r.size(), 6);
-        assert_eq!(l.fold().0, (0..4).sum());
-        assert_eq!(r.fold().0, (4..10).sum());
+        assert_eq!(l.all_fold().0, (0..4).sum());
+        assert_eq!(r.all_fold().0, (4..10).sum());
+    }
+}
+
+#[cfg(test)]
+mod avlarray_sequence_test {
+    use crate::data_structures::avl_tree::avl_tree_array::AVLTree;
+
+    sequence_test! { AVLTree; height }
+}
+
+#[cfg(test)]
+mod avlarray_iter_test {
+    use crate::data_structures::node_traits::*;
+    use crate::data_structures::avl_tree::avl_tree_array::AVLTree;
+    use crate::algebra::*;
+
+    def_node! { NodeTest, usize; size, height, rev, }
+
+    #[test]
+    fn iter_test() {
+        let mut arr = (0..10).collect::<AVLTree<NodeTest>>();
+        assert_eq!(format!("{:?}", arr), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
+        arr.reverse_range(2..8);
+        assert_eq!(arr.iter().cloned().collect::<Vec<_>>(), vec![0, 1, 7, 6, 5, 4, 3, 2, 8, 9]);
+        assert_eq!(arr.range(1..4).rev().cloned().collect::<Vec<_>>(), vec![6, 7, 1]);
+        for n in 0..12 {
+            let mut arr = (0..n).collect::<AVLTree<NodeTest>>();
+            for l in 0..n + 1 {
+                for r in l..n + 1 {
+                    let mut it = arr.range(l..r);
+                    assert_eq!(it.len(), r - l);
+                    let mut res = Vec::new();
+                    while let Some(&x) = it.next() {
+                        res.push(x);
+                        if let Some(&y) = it.next_back() { res.push(y); }
+                    }
+                    res.sort();
+                    assert_eq!(res, (l..r).collect::<Vec<_>>());
+                }
+            }
+            assert_eq!(arr.into_iter().collect::<Vec<_>>(), (0..n).collect::<Vec<_>>());
+        }
+    }
+
+    monoid! { #[derive(Debug)] V, i64, 0, |a, b| a + b }
+    monoid! { Add, i64, 0, |a, b| a + b }
+    action! { Add => V, |x, f| x + f }
+
+    def_node! { LazyNode, V; size, height, rev, lazy(Add), }
+
+    #[test]
+    fn debug_pending_test() {
+        let mut arr = AVLTree::<LazyNode>::from_vec((0..8).map(V).collect());
+        arr.reverse_range(..);
+        arr.update(0..4, Add(100));
+        arr.reverse_range(2..6);
+        arr.update(3..8, Add(1000));
+        let shown = format!("{:?}", arr);
+        assert_eq!(shown, "[V(107), V(106), V(2), V(1003), V(1104), V(1105), V(1001), V(1000)]");
+        assert_eq!(shown, format!("{:?}", arr.to_vec()));
     }
 }
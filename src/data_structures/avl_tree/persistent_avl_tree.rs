@@ -0,0 +1,478 @@
+use crate::algebra::*;
+
+use std::rc::Rc;
+use crate::data_structures::node_seq::bounds;
+
+use std::ops::RangeBounds;
+
+type Link<T, E> = Option<Rc<Node<T, E>>>;
+
+struct Node<T: Monoid + Effect<E>, E: Monoid> {
+    val: T,
+    fold: T,
+    lazy: Option<E>,
+    size: usize,
+    height: isize,
+    left: Link<T, E>,
+    right: Link<T, E>,
+}
+
+fn size<T: Monoid + Effect<E>, E: Monoid>(t: &Link<T, E>) -> usize {
+    t.as_ref().map_or(0, |n| n.size)
+}
+
+fn height<T: Monoid + Effect<E>, E: Monoid>(t: &Link<T, E>) -> isize {
+    t.as_ref().map_or(0, |n| n.height)
+}
+
+fn fold<T: Monoid + Effect<E>, E: Monoid>(t: &Link<T, E>) -> T {
+    t.as_ref().map_or_else(T::identity, |n| n.fold.clone())
+}
+
+fn fold_range<T: Monoid + Effect<E>, E: Monoid>(t: &Link<T, E>, l: usize, r: usize) -> T {
+    match *t {
+        Some(ref n) if l < r => {
+            if l == 0 && r == n.size { return n.fold.clone() }
+            let ls = size(&n.left);
+            let left = fold_range(&n.left, l.min(ls), r.min(ls));
+            let right = fold_range(&n.right, l.saturating_sub(ls + 1), r.saturating_sub(ls + 1));
+            let (left, right) = match n.lazy {
+                Some(ref e) => (left.effect(e), right.effect(e)),
+                None => (left, right),
+            };
+            if l <= ls && ls < r { left.op(&n.val).op(&right) }
+            else { left.op(&right) }
+        }
+        _ => T::identity(),
+    }
+}
+
+fn get<T: Monoid + Effect<E>, E: Monoid>(n: &Node<T, E>, i: usize) -> T {
+    let ls = size(&n.left);
+    let res = match i.cmp(&ls) {
+        std::cmp::Ordering::Less => get(n.left.as_ref().unwrap(), i),
+        std::cmp::Ordering::Equal => return n.val.clone(),
+        std::cmp::Ordering::Greater => get(n.right.as_ref().unwrap(), i - ls - 1),
+    };
+    match n.lazy {
+        Some(ref e) => res.effect(e),
+        None => res,
+    }
+}
+
+// `acc` is the composition of the pending actions of the ancestors
+fn collect<T: Monoid + Effect<E>, E: Monoid>(t: &Link<T, E>, acc: Option<&E>, out: &mut Vec<T>) {
+    if let Some(ref n) = *t {
+        let e = match (n.lazy.as_ref(), acc) {
+            (Some(l), Some(a)) => Some(l.op(a)),
+            (Some(l), None) => Some(l.clone()),
+            (None, a) => a.cloned(),
+        };
+        collect(&n.left, e.as_ref(), out);
+        out.push(match acc {
+            Some(a) => n.val.effect(a),
+            None => n.val.clone(),
+        });
+        collect(&n.right, e.as_ref(), out);
+    }
+}
+
+// every new node is allocated through a builder, which counts them
+struct Builder {
+    created: usize,
+}
+
+impl Builder {
+    fn node<T: Monoid + Effect<E>, E: Monoid>(&mut self, left: Link<T, E>, val: T, right: Link<T, E>) -> Rc<Node<T, E>> {
+        self.created += 1;
+        Rc::new(Node {
+            fold: fold(&left).op(&val).op(&fold(&right)),
+            size: size(&left) + size(&right) + 1,
+            height: std::cmp::max(height(&left), height(&right)) + 1,
+            lazy: None,
+            val,
+            left,
+            right,
+        })
+    }
+    fn effect<T: Monoid + Effect<E>, E: Monoid>(&mut self, n: &Node<T, E>, e: &E) -> Rc<Node<T, E>> {
+        self.created += 1;
+        Rc::new(Node {
+            val: n.val.effect(e),
+            fold: n.fold.effect(e),
+            lazy: Some(match n.lazy {
+                Some(ref l) => l.op(e),
+                None => e.clone(),
+            }),
+            size: n.size,
+            height: n.height,
+            left: n.left.clone(),
+            right: n.right.clone(),
+        })
+    }
+    // the children and the value of `n`, with the pending action copied down
+    fn expose<T: Monoid + Effect<E>, E: Monoid>(&mut self, n: &Node<T, E>) -> (Link<T, E>, T, Link<T, E>) {
+        match n.lazy {
+            Some(ref e) => (
+                n.left.as_ref().map(|c| self.effect(c, e)),
+                n.val.clone(),
+                n.right.as_ref().map(|c| self.effect(c, e)),
+            ),
+            None => (n.left.clone(), n.val.clone(), n.right.clone()),
+        }
+    }
+    fn build<T: Monoid + Effect<E>, E: Monoid>(&mut self, arr: &[T]) -> Link<T, E> {
+        if arr.is_empty() { return None }
+        let m = arr.len() / 2;
+        let left = self.build(&arr[..m]);
+        let right = self.build(&arr[m + 1..]);
+        Some(self.node(left, arr[m].clone(), right))
+    }
+    // (a, x, (b, y, c)) -> ((a, x, b), y, c)
+    fn rotate_left<T: Monoid + Effect<E>, E: Monoid>(&mut self, a: Link<T, E>, x: T, t: &Node<T, E>) -> Rc<Node<T, E>> {
+        let (b, y, c) = self.expose(t);
+        let l = self.node(a, x, b);
+        self.node(Some(l), y, c)
+    }
+    // ((a, x, b), y, c) -> (a, x, (b, y, c))
+    fn rotate_right<T: Monoid + Effect<E>, E: Monoid>(&mut self, t: &Node<T, E>, y: T, c: Link<T, E>) -> Rc<Node<T, E>> {
+        let (a, x, b) = self.expose(t);
+        let r = self.node(b, y, c);
+        self.node(a, x, Some(r))
+    }
+    fn join_right<T: Monoid + Effect<E>, E: Monoid>(&mut self, l: &Node<T, E>, v: T, r: Link<T, E>) -> Rc<Node<T, E>> {
+        let (ll, lv, lr) = self.expose(l);
+        if height(&lr) <= height(&r) + 1 {
+            if height(&lr).max(height(&r)) <= height(&ll) {
+                let t = self.node(lr, v, r);
+                self.node(ll, lv, Some(t))
+            }
+            else {
+                let t = self.rotate_right(lr.as_ref().unwrap(), v, r);
+                let (tl, tv, tr) = (t.left.clone(), t.val.clone(), t.right.clone());
+                let t = self.node(ll, lv, tl);
+                self.node(Some(t), tv, tr)
+            }
+        }
+        else {
+            let t = self.join_right(lr.as_ref().unwrap(), v, r);
+            if t.height <= height(&ll) + 1 { self.node(ll, lv, Some(t)) }
+            else { self.rotate_left(ll, lv, &t) }
+        }
+    }
+    fn join_left<T: Monoid + Effect<E>, E: Monoid>(&mut self, l: Link<T, E>, v: T, r: &Node<T, E>) -> Rc<Node<T, E>> {
+        let (rl, rv, rr) = self.expose(r);
+        if height(&rl) <= height(&l) + 1 {
+            if height(&rl).max(height(&l)) <= height(&rr) {
+                let t = self.node(l, v, rl);
+                self.node(Some(t), rv, rr)
+            }
+            else {
+                let t = self.rotate_left(l, v, rl.as_ref().unwrap());
+                let (tl, tv, tr) = (t.left.clone(), t.val.clone(), t.right.clone());
+                let t = self.node(tr, rv, rr);
+                self.node(tl, tv, Some(t))
+            }
+        }
+        else {
+            let t = self.join_left(l, v, rl.as_ref().unwrap());
+            if t.height <= height(&rr) + 1 { self.node(Some(t), rv, rr) }
+            else { self.rotate_right(&t, rv, rr) }
+        }
+    }
+    fn join<T: Monoid + Effect<E>, E: Monoid>(&mut self, l: Link<T, E>, v: T, r: Link<T, E>) -> Rc<Node<T, E>> {
+        if height(&l) > height(&r) + 1 { self.join_right(l.as_ref().unwrap(), v, r) }
+        else if height(&r) > height(&l) + 1 { self.join_left(l, v, r.as_ref().unwrap()) }
+        else { self.node(l, v, r) }
+    }
+    fn split_last<T: Monoid + Effect<E>, E: Monoid>(&mut self, n: &Node<T, E>) -> (Link<T, E>, T) {
+        let (l, v, r) = self.expose(n);
+        match r {
+            Some(r) => {
+                let (rl, rv) = self.split_last(&r);
+                (Some(self.join(l, v, rl)), rv)
+            }
+            None => (l, v),
+        }
+    }
+    fn merge<T: Monoid + Effect<E>, E: Monoid>(&mut self, l: &Link<T, E>, r: &Link<T, E>) -> Link<T, E> {
+        match (l, r) {
+            (Some(ln), Some(_)) => {
+                let (l, v) = self.split_last(ln);
+                Some(self.join(l, v, r.clone()))
+            }
+            (None, _) => r.clone(),
+            (_, None) => l.clone(),
+        }
+    }
+    fn split<T: Monoid + Effect<E>, E: Monoid>(&mut self, t: &Link<T, E>, k: usize) -> (Link<T, E>, Link<T, E>) {
+        match *t {
+            Some(ref n) if 0 < k && k < n.size => {
+                let (l, v, r) = self.expose(n);
+                let ls = size(&l);
+                if k <= ls {
+                    let (a, b) = self.split(&l, k);
+                    (a, Some(self.join(b, v, r)))
+                }
+                else {
+                    let (a, b) = self.split(&r, k - ls - 1);
+                    (Some(self.join(l, v, a)), b)
+                }
+            }
+            _ if k == 0 => (None, t.clone()),
+            _ => (t.clone(), None),
+        }
+    }
+}
+
+/// Persistent sequence on an AVL tree whose nodes are shared between versions.
+/// Every operation returns a new version in O(log n) and keeps `self` intact.
+///
+/// Pushing a lazy action copies the children, so the number of allocated nodes
+/// grows with the history. Once more than the rebuild limit of nodes has been
+/// allocated since the last rebuild, the version is rebuilt from scratch,
+/// which drops the references to the nodes of older versions.
+pub struct PersistentAVLTree<T: Monoid + Effect<E>, E: Monoid> {
+    root: Link<T, E>,
+    created: usize,
+    limit: usize,
+}
+
+impl<T: Monoid + Effect<E>, E: Monoid> Clone for PersistentAVLTree<T, E> {
+    fn clone(&self) -> Self {
+        Self { root: self.root.clone(), created: self.created, limit: self.limit }
+    }
+}
+
+impl<T: Monoid + Effect<E>, E: Monoid> Default for PersistentAVLTree<T, E> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<T: Monoid + Effect<E>, E: Monoid> PersistentAVLTree<T, E> {
+    pub fn new() -> Self {
+        Self { root: None, created: 0, limit: usize::MAX }
+    }
+    pub fn from_slice(arr: &[T]) -> Self {
+        Self { root: Builder { created: 0 }.build(arr), created: 0, limit: usize::MAX }
+    }
+    /// rebuilds automatically once more than `limit` nodes have been allocated
+    pub fn with_rebuild_limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+    fn version(&self, root: Link<T, E>, created: usize) -> Self {
+        let res = Self { root, created, limit: self.limit };
+        if res.created > res.limit { res.rebuild() }
+        else { res }
+    }
+    fn with_builder<F: FnOnce(&mut Builder) -> Link<T, E>>(&self, created: usize, f: F) -> Self {
+        let mut b = Builder { created: 0 };
+        let root = f(&mut b);
+        self.version(root, created.saturating_add(b.created))
+    }
+    pub fn rebuild(&self) -> Self {
+        Self { root: Builder { created: 0 }.build(&self.to_vec()), created: 0, limit: self.limit }
+    }
+    pub fn len(&self) -> usize { size(&self.root) }
+    pub fn is_empty(&self) -> bool { self.root.is_none() }
+    pub fn get(&self, i: usize) -> T {
+        assert!(i < self.len());
+        get(self.root.as_ref().unwrap(), i)
+    }
+    pub fn all_fold(&self) -> T { fold(&self.root) }
+    pub fn fold<R: RangeBounds<usize>>(&self, ran: R) -> T {
+        let (l, r) = bounds(ran, self.len());
+        fold_range(&self.root, l, r)
+    }
+    pub fn merge(&self, right: &Self) -> Self {
+        self.with_builder(self.created.saturating_add(right.created), |b| b.merge(&self.root, &right.root))
+    }
+    pub fn split(&self, k: usize) -> (Self, Self) {
+        assert!(k <= self.len());
+        let mut b = Builder { created: 0 };
+        let (l, r) = b.split(&self.root, k);
+        let created = self.created.saturating_add(b.created);
+        (self.version(l, created), self.version(r, created))
+    }
+    /// the subsequence in `ran` as a new version
+    pub fn slice<R: RangeBounds<usize>>(&self, ran: R) -> Self {
+        let (l, r) = bounds(ran, self.len());
+        self.with_builder(self.created, |b| {
+            let (_, bc) = b.split(&self.root, l);
+            b.split(&bc, r - l).0
+        })
+    }
+    /// the sequence concatenated `k` times, built by doubling
+    pub fn repeat(&self, mut k: usize) -> Self {
+        let mut res = Self::new().with_rebuild_limit(self.limit);
+        let mut x = self.clone();
+        while k > 0 {
+            if k & 1 == 1 { res = res.merge(&x); }
+            k >>= 1;
+            if k > 0 { x = x.merge(&x); }
+        }
+        res
+    }
+    pub fn insert(&self, pos: usize, val: T) -> Self {
+        assert!(pos <= self.len());
+        self.with_builder(self.created, |b| {
+            let (l, r) = b.split(&self.root, pos);
+            Some(b.join(l, val, r))
+        })
+    }
+    pub fn erase(&self, pos: usize) -> Self {
+        assert!(pos < self.len());
+        self.with_builder(self.created, |b| {
+            let (l, mr) = b.split(&self.root, pos);
+            let (_, r) = b.split(&mr, 1);
+            b.merge(&l, &r)
+        })
+    }
+    pub fn set(&self, pos: usize, val: T) -> Self {
+        assert!(pos < self.len());
+        self.with_builder(self.created, |b| {
+            let (l, mr) = b.split(&self.root, pos);
+            let (_, r) = b.split(&mr, 1);
+            Some(b.join(l, val, r))
+        })
+    }
+    pub fn update<R: RangeBounds<usize>>(&self, ran: R, e: E) -> Self {
+        let (l, r) = bounds(ran, self.len());
+        self.with_builder(self.created, |b| {
+            let (a, bc) = b.split(&self.root, l);
+            let (m, c) = b.split(&bc, r - l);
+            let m = m.map(|n| b.effect(&n, &e));
+            let am = b.merge(&a, &m);
+            b.merge(&am, &c)
+        })
+    }
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut res = Vec::with_capacity(self.len());
+        collect(&self.root, None, &mut res);
+        res
+    }
+}
+
+impl<T: Monoid + Effect<E>, E: Monoid> std::iter::FromIterator<T> for PersistentAVLTree<T, E> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_slice(&iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<T: Monoid + Effect<E> + std::fmt::Debug, E: Monoid> std::fmt::Debug for PersistentAVLTree<T, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.to_vec()).finish()
+    }
+}
+
+#[cfg(test)]
+mod persistent_avl_tree_test {
+    use crate::random::{ Random, Xorshift128 };
+    use crate::data_structures::avl_tree::persistent_avl_tree::*;
+
+    monoid! { #[derive(Debug)] Sm, (i64, i64), (0, 0), |a, b| (a.0 + b.0, a.1 + b.1) }
+    monoid! { Aq, i64, 0, |a, b| a + b }
+    action! { Aq => Sm, |x, f| (x.0 + f * x.1, x.1) }
+
+    fn check_balance(t: &Link<Sm, Aq>) -> isize {
+        match *t {
+            Some(ref n) => {
+                let (l, r) = (check_balance(&n.left), check_balance(&n.right));
+                assert!((l - r).abs() <= 1);
+                assert_eq!(n.height, l.max(r) + 1);
+                n.height
+            }
+            None => 0,
+        }
+    }
+
+    fn leaf(x: i64) -> Sm { Sm((x, 1)) }
+    fn sums(v: &[Sm]) -> Vec<i64> { v.iter().map(|s| s.0.0).collect() }
+
+    #[test]
+    fn copy_paste_test() {
+        let t: PersistentAVLTree<Sm, Aq> = (0..5).map(leaf).collect();
+        let part = t.slice(1..3);
+        let (l, r) = t.split(4);
+        let t2 = l.merge(&part.repeat(3)).merge(&r);
+        assert_eq!(sums(&t2.to_vec()), vec![0, 1, 2, 3, 1, 2, 1, 2, 1, 2, 4]);
+        assert_eq!(sums(&t.to_vec()), vec![0, 1, 2, 3, 4]);
+        let t3 = t2.update(3..6, Aq(10));
+        assert_eq!(t3.fold(..).0.0, 19 + 30);
+        assert_eq!(t3.get(4).0.0, 11);
+        assert_eq!(t2.get(4).0.0, 1);
+
+        let big = part.repeat(1 << 40);
+        assert_eq!(big.len(), 1 << 41);
+        assert_eq!(big.all_fold().0.0, 3 << 40);
+        let big = big.update(1..(1 << 41) - 1, Aq(1));
+        assert_eq!(big.fold(2..6).0.0, 2 + 3 + 2 + 3);
+        assert_eq!(big.get((1 << 41) - 1).0.0, 2);
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(45);
+        let mut versions: Vec<(PersistentAVLTree<Sm, Aq>, Vec<i64>)> = vec![(PersistentAVLTree::new().with_rebuild_limit(300), Vec::new())];
+        for _ in 0..3000 {
+            let (t, v) = versions[(rng.rand_u64() % versions.len() as u64) as usize].clone();
+            let n = v.len() as u64;
+            let next = match rng.rand_u64() % 6 {
+                0 => {
+                    let p = (rng.rand_u64() % (n + 1)) as usize;
+                    let x = (rng.rand_u64() % 100) as i64;
+                    let mut w = v.clone();
+                    w.insert(p, x);
+                    (t.insert(p, leaf(x)), w)
+                }
+                1 if n > 0 => {
+                    let p = (rng.rand_u64() % n) as usize;
+                    let mut w = v.clone();
+                    w.remove(p);
+                    (t.erase(p), w)
+                }
+                2 => {
+                    let l = (rng.rand_u64() % (n + 1)) as usize;
+                    let r = l + (rng.rand_u64() % (n - l as u64 + 1)) as usize;
+                    let x = (rng.rand_u64() % 100) as i64;
+                    let mut w = v.clone();
+                    w[l..r].iter_mut().for_each(|y| *y += x);
+                    (t.update(l..r, Aq(x)), w)
+                }
+                3 if n < 300 => {
+                    let (u, w) = versions[(rng.rand_u64() % versions.len() as u64) as usize].clone();
+                    let l = (rng.rand_u64() % (w.len() as u64 + 1)) as usize;
+                    let r = l + (rng.rand_u64() % (w.len() - l + 1) as u64) as usize;
+                    let p = (rng.rand_u64() % (n + 1)) as usize;
+                    let (a, c) = t.split(p);
+                    let mut z = v[..p].to_vec();
+                    z.extend_from_slice(&w[l..r]);
+                    z.extend_from_slice(&v[p..]);
+                    (a.merge(&u.slice(l..r)).merge(&c), z)
+                }
+                4 if n > 0 => {
+                    let p = (rng.rand_u64() % n) as usize;
+                    let x = (rng.rand_u64() % 100) as i64;
+                    let mut w = v.clone();
+                    w[p] = x;
+                    (t.set(p, leaf(x)), w)
+                }
+                _ => {
+                    let l = (rng.rand_u64() % (n + 1)) as usize;
+                    let r = l + (rng.rand_u64() % (n - l as u64 + 1)) as usize;
+                    assert_eq!(t.fold(l..r).0.0, v[l..r].iter().sum::<i64>());
+                    assert_eq!(t.fold(l..r).0.1, (r - l) as i64);
+                    if l < r { assert_eq!(t.get(l).0.0, v[l]); }
+                    (t, v)
+                }
+            };
+            assert_eq!(sums(&next.0.to_vec()), next.1);
+            assert_eq!(next.0.len(), next.1.len());
+            check_balance(&next.0.root);
+            versions.push(next);
+        }
+        for (t, v) in versions.iter() {
+            assert_eq!(sums(&t.to_vec()), *v);
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod fenwick_tree;
+pub mod range_fenwick_tree;
+pub mod fenwick_tree_2d;
+pub mod compressed_fenwick_tree;
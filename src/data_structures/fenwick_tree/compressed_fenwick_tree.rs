@@ -0,0 +1,65 @@
+use crate::algebra::*;
+use crate::data_structures::fenwick_tree::fenwick_tree::FenwickTree;
+
+use std::ops::Range;
+
+pub struct CompressedFenwickTree<K: Ord, T: Monoid + Inv + Commutative> {
+    keys: Vec<K>,
+    fen: FenwickTree<T>,
+}
+
+impl<K: Ord, T: Monoid + Inv + Commutative> CompressedFenwickTree<K, T> {
+    pub fn new(mut keys: Vec<K>) -> Self {
+        keys.sort();
+        keys.dedup();
+        let fen = FenwickTree::new(keys.len());
+        CompressedFenwickTree { keys, fen }
+    }
+
+    pub fn add(&mut self, k: &K, x: &T) {
+        let i = self.keys.binary_search(k).expect("the key is not registered");
+        self.fen.add(i, x);
+    }
+
+    pub fn fold(&self, ran: Range<K>) -> T {
+        let l = self.keys.partition_point(|k| *k < ran.start);
+        let r = self.keys.partition_point(|k| *k < ran.end);
+        self.fen.fold(l..r)
+    }
+}
+
+#[cfg(test)]
+mod compressed_fenwick_tree_test {
+    use crate::algebra::*;
+    use crate::data_structures::fenwick_tree::compressed_fenwick_tree::CompressedFenwickTree;
+
+    #[derive(Clone)]
+    struct Am(i64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Commutative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+    impl Inv for Am {
+        fn inv(&self) -> Self { Am(-self.0) }
+    }
+
+    #[test]
+    fn compressed_test() {
+        let keys = vec![-1_000_000_000_000i64, 5, 3, 1_000_000_000_000, 5, -7];
+        let mut fen = CompressedFenwickTree::new(keys);
+        fen.add(&-1_000_000_000_000, &Am(1));
+        fen.add(&5, &Am(10));
+        fen.add(&3, &Am(100));
+        fen.add(&-7, &Am(1000));
+        fen.add(&1_000_000_000_000, &Am(10000));
+        assert_eq!(fen.fold(-7..6).0, 1110);
+        assert_eq!(fen.fold(-6..5).0, 100);
+        assert_eq!(fen.fold(i64::MIN..i64::MAX).0, 11111);
+        assert_eq!(fen.fold(6..1_000_000_000_000).0, 0);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::algebra::*;
+
+use std::ops::Range;
+
+pub struct FenwickTree<T: Monoid + Inv + Commutative> {
+    node: Vec<T>,
+}
+
+impl<T: Monoid + Inv + Commutative> FenwickTree<T> {
+    pub fn new(n: usize) -> Self {
+        FenwickTree { node: vec![T::identity(); n + 1] }
+    }
+
+    pub fn init(arr: &[T]) -> Self {
+        let mut node = vec![T::identity(); arr.len() + 1];
+        node[1..].clone_from_slice(arr);
+        for i in 1..node.len() {
+            let j = i + (i & i.wrapping_neg());
+            if j < node.len() { node[j] = node[j].op(&node[i]); }
+        }
+        FenwickTree { node }
+    }
+
+    pub fn len(&self) -> usize { self.node.len() - 1 }
+
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    pub fn add(&mut self, i: usize, x: &T) {
+        let mut i = i + 1;
+        while i < self.node.len() {
+            self.node[i] = self.node[i].op(x);
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    pub fn prefix(&self, r: usize) -> T {
+        let mut r = r;
+        let mut sm = T::identity();
+        while r > 0 {
+            sm = sm.op(&self.node[r]);
+            r &= r - 1;
+        }
+        sm
+    }
+
+    pub fn fold(&self, ran: Range<usize>) -> T {
+        self.prefix(ran.start).inv().op(&self.prefix(ran.end))
+    }
+
+    pub fn get(&self, i: usize) -> T { self.fold(i..i + 1) }
+
+    pub fn set(&mut self, i: usize, x: T) {
+        let d = self.get(i).inv().op(&x);
+        self.add(i, &d);
+    }
+
+    pub fn max_right<F>(&self, pred: F) -> usize where F: Fn(&T) -> bool {
+        assert!(pred(&T::identity()));
+        let mut i = 0;
+        let mut sm = T::identity();
+        let mut k = (self.node.len() - 1).next_power_of_two();
+        while k > 0 {
+            if i + k < self.node.len() {
+                let res = sm.op(&self.node[i + k]);
+                if pred(&res) {
+                    sm = res;
+                    i += k;
+                }
+            }
+            k >>= 1;
+        }
+        i
+    }
+
+    pub fn lower_bound(&self, x: &T) -> usize where T: Ord {
+        self.max_right(|s| s < x)
+    }
+}
+
+#[cfg(test)]
+mod fenwick_tree_test {
+    use crate::algebra::*;
+    use crate::data_structures::fenwick_tree::fenwick_tree::FenwickTree;
+
+    #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+    struct Am(i64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Commutative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+    impl Inv for Am {
+        fn inv(&self) -> Self { Am(-self.0) }
+    }
+
+    #[test]
+    fn fenwick_tree_test() {
+        let arr = [3, 1, 4, 1, 5, 9, 2, 6];
+        let mut fen = FenwickTree::init(&arr.iter().map(|&x| Am(x)).collect::<Vec<_>>());
+        for l in 0..arr.len() + 1 {
+            for r in l..arr.len() + 1 {
+                assert_eq!(fen.fold(l..r).0, arr[l..r].iter().sum::<i64>());
+            }
+        }
+        fen.add(2, &Am(10));
+        fen.set(5, Am(0));
+        assert_eq!(fen.get(2).0, 14);
+        assert_eq!(fen.fold(0..8).0, 3 + 1 + 14 + 1 + 5 + 2 + 6);
+        assert_eq!(fen.lower_bound(&Am(4)), 1);
+        assert_eq!(fen.lower_bound(&Am(5)), 2);
+        assert_eq!(fen.lower_bound(&Am(18)), 2);
+        assert_eq!(fen.lower_bound(&Am(19)), 3);
+        assert_eq!(fen.lower_bound(&Am(100)), 8);
+        assert_eq!(fen.max_right(|s| s.0 <= 24), 6);
+    }
+}
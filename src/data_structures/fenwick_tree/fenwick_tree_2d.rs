@@ -0,0 +1,88 @@
+use crate::algebra::*;
+
+use std::ops::Range;
+
+pub struct FenwickTree2D<T: Monoid + Inv + Commutative> {
+    node: Vec<Vec<T>>,
+}
+
+impl<T: Monoid + Inv + Commutative> FenwickTree2D<T> {
+    pub fn new(h: usize, w: usize) -> Self {
+        FenwickTree2D { node: vec![vec![T::identity(); w + 1]; h + 1] }
+    }
+
+    pub fn add(&mut self, i: usize, j: usize, x: &T) {
+        let mut i = i + 1;
+        while i < self.node.len() {
+            let row = &mut self.node[i];
+            let mut j = j + 1;
+            while j < row.len() {
+                row[j] = row[j].op(x);
+                j += j & j.wrapping_neg();
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    pub fn prefix(&self, i: usize, j: usize) -> T {
+        let mut sm = T::identity();
+        let mut i = i;
+        while i > 0 {
+            let mut j = j;
+            while j > 0 {
+                sm = sm.op(&self.node[i][j]);
+                j &= j - 1;
+            }
+            i &= i - 1;
+        }
+        sm
+    }
+
+    pub fn fold(&self, rows: Range<usize>, cols: Range<usize>) -> T {
+        self.prefix(rows.end, cols.end)
+            .op(&self.prefix(rows.start, cols.end).inv())
+            .op(&self.prefix(rows.end, cols.start).inv())
+            .op(&self.prefix(rows.start, cols.start))
+    }
+}
+
+#[cfg(test)]
+mod fenwick_tree_2d_test {
+    use crate::algebra::*;
+    use crate::data_structures::fenwick_tree::fenwick_tree_2d::FenwickTree2D;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[derive(Clone)]
+    struct Am(i64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Commutative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+    impl Inv for Am {
+        fn inv(&self) -> Self { Am(-self.0) }
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(9);
+        let (h, w) = (6, 9);
+        let mut arr = vec![vec![0i64; w]; h];
+        let mut fen = FenwickTree2D::new(h, w);
+        for _ in 0..500 {
+            let (i, j, x) = ((rng.rand_u64() % h as u64) as usize, (rng.rand_u64() % w as u64) as usize, (rng.rand_u64() % 100) as i64);
+            fen.add(i, j, &Am(x));
+            arr[i][j] += x;
+            let u = (rng.rand_u64() % (h as u64 + 1)) as usize;
+            let d = u + (rng.rand_u64() % ((h - u) as u64 + 1)) as usize;
+            let l = (rng.rand_u64() % (w as u64 + 1)) as usize;
+            let r = l + (rng.rand_u64() % ((w - l) as u64 + 1)) as usize;
+            let expected = arr[u..d].iter().map(|row| row[l..r].iter().sum::<i64>()).sum::<i64>();
+            assert_eq!(fen.fold(u..d, l..r).0, expected);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::algebra::*;
+use crate::data_structures::fenwick_tree::fenwick_tree::FenwickTree;
+
+use std::ops::Range;
+
+fn times<T: Monoid>(x: &T, mut k: usize) -> T {
+    let mut ans = T::identity();
+    let mut x = x.clone();
+    while k > 0 {
+        if k & 1 == 1 { ans = ans.op(&x); }
+        x = x.op(&x);
+        k >>= 1;
+    }
+    ans
+}
+
+pub struct RangeFenwickTree<T: Monoid + Inv + Commutative> {
+    b1: FenwickTree<T>,
+    b2: FenwickTree<T>,
+}
+
+impl<T: Monoid + Inv + Commutative> RangeFenwickTree<T> {
+    pub fn new(n: usize) -> Self {
+        RangeFenwickTree { b1: FenwickTree::new(n + 1), b2: FenwickTree::new(n + 1) }
+    }
+
+    pub fn add(&mut self, ran: Range<usize>, x: &T) {
+        let (l, r) = (ran.start, ran.end);
+        self.b1.add(l, x);
+        self.b1.add(r, &x.inv());
+        self.b2.add(l, &times(x, l));
+        self.b2.add(r, &times(x, r).inv());
+    }
+
+    pub fn prefix(&self, r: usize) -> T {
+        times(&self.b1.prefix(r), r).op(&self.b2.prefix(r).inv())
+    }
+
+    pub fn fold(&self, ran: Range<usize>) -> T {
+        self.prefix(ran.start).inv().op(&self.prefix(ran.end))
+    }
+}
+
+#[cfg(test)]
+mod range_fenwick_tree_test {
+    use crate::algebra::*;
+    use crate::data_structures::fenwick_tree::range_fenwick_tree::RangeFenwickTree;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[derive(Clone)]
+    struct Am(i64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Commutative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+    impl Inv for Am {
+        fn inv(&self) -> Self { Am(-self.0) }
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(8);
+        let n = 20;
+        let mut arr = vec![0i64; n];
+        let mut fen = RangeFenwickTree::new(n);
+        for _ in 0..500 {
+            let l = (rng.rand_u64() % (n as u64 + 1)) as usize;
+            let r = l + (rng.rand_u64() % ((n - l) as u64 + 1)) as usize;
+            if rng.rand_u64() & 1 == 0 {
+                let x = (rng.rand_u64() % 201) as i64 - 100;
+                fen.add(l..r, &Am(x));
+                for a in arr[l..r].iter_mut() { *a += x; }
+            }
+            else {
+                assert_eq!(fen.fold(l..r).0, arr[l..r].iter().sum::<i64>());
+            }
+        }
+    }
+}
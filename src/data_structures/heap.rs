@@ -1,2 +1,23 @@
 pub mod persistent_leftist_heap;
+pub mod pairing_heap;
+pub mod lazy_leftist_heap;
+pub mod radix_heap;
+pub mod bucket_queue;
 pub mod van_emde_boas_tree;
+
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+
+pub trait PriorityQueue<K, V> {
+    fn push(&mut self, key: K, val: V);
+    // pops an element with the minimum key
+    fn pop(&mut self) -> Option<(K, V)>;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool { self.len() == 0 }
+}
+
+impl<K: Ord, V: Ord> PriorityQueue<K, V> for BinaryHeap<Reverse<(K, V)>> {
+    fn push(&mut self, key: K, val: V) { BinaryHeap::push(self, Reverse((key, val))); }
+    fn pop(&mut self) -> Option<(K, V)> { BinaryHeap::pop(self).map(|Reverse(e)| e) }
+    fn len(&self) -> usize { BinaryHeap::len(self) }
+}
@@ -0,0 +1,71 @@
+use super::PriorityQueue;
+
+// the keys pushed must lie in [last popped key, last popped key + max_diff]
+pub struct BucketQueue<V> {
+    bucket: Vec<Vec<V>>,
+    cur: u64,
+    len: usize,
+}
+
+impl<V> BucketQueue<V> {
+    pub fn new(max_diff: usize) -> Self {
+        BucketQueue { bucket: (0..max_diff + 1).map(|_| Vec::new()).collect(), cur: 0, len: 0 }
+    }
+
+    fn index(&self, key: u64) -> usize {
+        (key % self.bucket.len() as u64) as usize
+    }
+}
+
+impl<V> PriorityQueue<u64, V> for BucketQueue<V> {
+    fn push(&mut self, key: u64, val: V) {
+        assert!(self.cur <= key && key - self.cur < self.bucket.len() as u64);
+        let i = self.index(key);
+        self.bucket[i].push(val);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<(u64, V)> {
+        if self.len == 0 { return None }
+        loop {
+            let i = self.index(self.cur);
+            if let Some(val) = self.bucket[i].pop() {
+                self.len -= 1;
+                return Some((self.cur, val));
+            }
+            self.cur += 1;
+        }
+    }
+
+    fn len(&self) -> usize { self.len }
+}
+
+#[cfg(test)]
+mod bucket_queue_test {
+    use crate::data_structures::heap::PriorityQueue;
+    use crate::data_structures::heap::bucket_queue::BucketQueue;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[test]
+    fn monotone_random_test() {
+        let mut rng = Xorshift128::new(41);
+        let mut que = BucketQueue::new(10);
+        let mut vec: Vec<(u64, usize)> = Vec::new();
+        let mut last = 0;
+        for i in 0..5000usize {
+            if rng.rand_u64() % 3 == 2 {
+                let p = (0..vec.len()).min_by_key(|&j| vec[j].0);
+                let expected = p.map(|p| vec.swap_remove(p).0);
+                let got = que.pop();
+                assert_eq!(got.as_ref().map(|e| e.0), expected);
+                if let Some((k, _)) = got { last = k; }
+            }
+            else {
+                let k = last + rng.rand_u64() % 11;
+                que.push(k, i);
+                vec.push((k, i));
+            }
+            assert_eq!(que.len(), vec.len());
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::cmp::Ordering;
+use std::ops::Add;
+
+struct Node<T> {
+    val: T,
+    // pending addition for the children
+    lazy: Option<T>,
+    rank: usize,
+    left: Option<Box<Node<T>>>,
+    right: Option<Box<Node<T>>>,
+}
+
+fn rank<T>(node: &Option<Box<Node<T>>>) -> usize {
+    node.as_ref().map_or(0, |nd| nd.rank)
+}
+
+fn apply<T: Clone + Add<Output = T>>(node: &mut Option<Box<Node<T>>>, x: &T) {
+    if let Some(ref mut nd) = *node {
+        nd.val = nd.val.clone() + x.clone();
+        nd.lazy = Some(match nd.lazy.take() {
+            Some(y) => y + x.clone(),
+            None => x.clone(),
+        });
+    }
+}
+
+fn push<T: Clone + Add<Output = T>>(node: &mut Node<T>) {
+    if let Some(x) = node.lazy.take() {
+        apply(&mut node.left, &x);
+        apply(&mut node.right, &x);
+    }
+}
+
+fn meld<T, F>(a: Option<Box<Node<T>>>, b: Option<Box<Node<T>>>, cmp: &F) -> Option<Box<Node<T>>>
+where T: Clone + Add<Output = T>, F: Fn(&T, &T) -> Ordering {
+    match (a, b) {
+        (None, b) => b,
+        (a, None) => a,
+        (Some(a), Some(b)) => {
+            let (mut a, b) = if cmp(&a.val, &b.val) == Ordering::Less { (b, a) } else { (a, b) };
+            push(&mut a);
+            a.right = meld(a.right.take(), Some(b), cmp);
+            if rank(&a.left) < rank(&a.right) { std::mem::swap(&mut a.left, &mut a.right); }
+            a.rank = rank(&a.right) + 1;
+            Some(a)
+        }
+    }
+}
+
+// the top is the greatest element with respect to `cmp`
+pub struct LazyLeftistHeap<T, F> {
+    root: Option<Box<Node<T>>>,
+    len: usize,
+    cmp: F,
+}
+
+impl<T, F> LazyLeftistHeap<T, F>
+where T: Clone + Add<Output = T>, F: Fn(&T, &T) -> Ordering {
+    pub fn new(cmp: F) -> Self {
+        LazyLeftistHeap { root: None, len: 0, cmp }
+    }
+
+    pub fn len(&self) -> usize { self.len }
+
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    pub fn push(&mut self, x: T) {
+        let node = Box::new(Node { val: x, lazy: None, rank: 1, left: None, right: None });
+        self.root = meld(self.root.take(), Some(node), &self.cmp);
+        self.len += 1;
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|nd| &nd.val)
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.root.take().map(|mut nd| {
+            push(&mut nd);
+            self.root = meld(nd.left.take(), nd.right.take(), &self.cmp);
+            self.len -= 1;
+            nd.val
+        })
+    }
+
+    // adds `x` to every element; the addition must not change their order
+    pub fn add_all(&mut self, x: T) {
+        apply(&mut self.root, &x);
+    }
+
+    pub fn meld(&mut self, mut other: Self) {
+        self.root = meld(self.root.take(), other.root.take(), &self.cmp);
+        self.len += other.len;
+    }
+}
+
+// the left spine can be O(n) deep, so the nodes are freed without recursion
+impl<T, F> Drop for LazyLeftistHeap<T, F> {
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        stack.extend(self.root.take());
+        while let Some(mut nd) = stack.pop() {
+            stack.extend(nd.left.take());
+            stack.extend(nd.right.take());
+        }
+    }
+}
+
+#[cfg(test)]
+mod lazy_leftist_heap_test {
+    use crate::data_structures::heap::lazy_leftist_heap::LazyLeftistHeap;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(83);
+        let cmp = |a: &i64, b: &i64| b.cmp(a);
+        let mut heaps = (0..5).map(|_| LazyLeftistHeap::new(cmp)).collect::<Vec<_>>();
+        let mut vecs = vec![Vec::new(); 5];
+        for _ in 0..2000 {
+            let i = (rng.rand_u64() % 5) as usize;
+            let x = (rng.rand_u64() % 1000) as i64 - 500;
+            match rng.rand_u64() % 5 {
+                0 | 1 => {
+                    heaps[i].push(x);
+                    vecs[i].push(x);
+                }
+                2 => {
+                    heaps[i].add_all(x);
+                    for v in vecs[i].iter_mut() { *v += x; }
+                }
+                3 => {
+                    let j = (rng.rand_u64() % 5) as usize;
+                    if i != j {
+                        let h = std::mem::replace(&mut heaps[j], LazyLeftistHeap::new(cmp));
+                        heaps[i].meld(h);
+                        let v = std::mem::take(&mut vecs[j]);
+                        vecs[i].extend(v);
+                    }
+                }
+                _ => {
+                    let expected = vecs[i].iter().cloned().min();
+                    if let Some(e) = expected {
+                        let p = vecs[i].iter().position(|&v| v == e).unwrap();
+                        vecs[i].swap_remove(p);
+                    }
+                    assert_eq!(heaps[i].pop(), expected);
+                }
+            }
+            assert_eq!(heaps[i].len(), vecs[i].len());
+            assert_eq!(heaps[i].peek(), vecs[i].iter().min());
+        }
+    }
+
+    #[test]
+    fn deep_drop_test() {
+        let mut heap = LazyLeftistHeap::new(|a: &i64, b: &i64| a.cmp(b));
+        for i in 0..1_000_000 { heap.push(i); }
+        assert_eq!(heap.peek(), Some(&999_999));
+        drop(heap);
+    }
+}
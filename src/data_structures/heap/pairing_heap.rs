@@ -0,0 +1,184 @@
+const NIL: usize = usize::MAX;
+
+struct Node<T> {
+    val: Option<T>,
+    child: usize,
+    next: usize,
+    // parent if this is the leftmost child, otherwise the left sibling
+    prev: usize,
+}
+
+// min-heap whose elements are addressed by the handle returned from `push`.
+// the handle of a popped element is reused by later pushes
+pub struct PairingHeap<T: Ord> {
+    node: Vec<Node<T>>,
+    free: Vec<usize>,
+    root: usize,
+    len: usize,
+}
+
+impl<T: Ord> Default for PairingHeap<T> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<T: Ord> PairingHeap<T> {
+    pub fn new() -> Self {
+        PairingHeap { node: Vec::new(), free: Vec::new(), root: NIL, len: 0 }
+    }
+
+    pub fn len(&self) -> usize { self.len }
+
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    fn link(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL { return b }
+        if b == NIL { return a }
+        let (a, b) = if self.node[b].val < self.node[a].val { (b, a) } else { (a, b) };
+        let c = self.node[a].child;
+        self.node[b].next = c;
+        if c != NIL { self.node[c].prev = b; }
+        self.node[b].prev = a;
+        self.node[a].child = b;
+        a
+    }
+
+    fn merge_pairs(&mut self, mut v: usize) -> usize {
+        let mut list = Vec::new();
+        while v != NIL {
+            let next = self.node[v].next;
+            self.node[v].next = NIL;
+            self.node[v].prev = NIL;
+            list.push(v);
+            v = next;
+        }
+        let paired = list.chunks(2)
+            .map(|c| if c.len() == 2 { self.link(c[0], c[1]) } else { c[0] })
+            .collect::<Vec<_>>();
+        paired.into_iter().rev().fold(NIL, |acc, v| self.link(v, acc))
+    }
+
+    pub fn push(&mut self, x: T) -> usize {
+        let nd = Node { val: Some(x), child: NIL, next: NIL, prev: NIL };
+        let h = match self.free.pop() {
+            Some(h) => { self.node[h] = nd; h }
+            None => { self.node.push(nd); self.node.len() - 1 }
+        };
+        self.root = self.link(self.root, h);
+        self.len += 1;
+        h
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        if self.root == NIL { None } else { self.node[self.root].val.as_ref() }
+    }
+
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        if self.root == NIL { return None }
+        let r = self.root;
+        let c = std::mem::replace(&mut self.node[r].child, NIL);
+        self.root = self.merge_pairs(c);
+        self.len -= 1;
+        self.free.push(r);
+        self.node[r].val.take().map(|x| (r, x))
+    }
+
+    // returns None if the element has been popped and the handle not reused yet
+    pub fn get(&self, h: usize) -> Option<&T> {
+        self.node[h].val.as_ref()
+    }
+
+    pub fn decrease_key(&mut self, h: usize, x: T) {
+        assert!(self.node[h].val.as_ref().map(|v| &x <= v).expect("the element has already been popped"));
+        self.node[h].val = Some(x);
+        if h == self.root { return }
+        let (p, n) = (self.node[h].prev, self.node[h].next);
+        if self.node[p].child == h { self.node[p].child = n; }
+        else { self.node[p].next = n; }
+        if n != NIL { self.node[n].prev = p; }
+        self.node[h].prev = NIL;
+        self.node[h].next = NIL;
+        self.root = self.link(self.root, h);
+    }
+
+    // moves the elements of `other` into `self`. returns the map from the handles of `other`
+    // to their handles in `self`
+    pub fn meld(&mut self, other: Self) -> impl Fn(usize) -> usize {
+        let offset = self.node.len();
+        let shift = move |v: usize| if v == NIL { NIL } else { v + offset };
+        let root = shift(other.root);
+        self.node.extend(other.node.into_iter().map(|nd| Node {
+            val: nd.val,
+            child: shift(nd.child),
+            next: shift(nd.next),
+            prev: shift(nd.prev),
+        }));
+        self.free.extend(other.free.into_iter().map(shift));
+        self.root = self.link(self.root, root);
+        self.len += other.len;
+        shift
+    }
+}
+
+#[cfg(test)]
+mod pairing_heap_test {
+    use crate::data_structures::heap::pairing_heap::PairingHeap;
+    use crate::random::{ Random, Xorshift128 };
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(38);
+        let mut heap = PairingHeap::new();
+        let mut set = BTreeSet::new();
+        let mut handles = Vec::new();
+        for _ in 0..3000 {
+            match rng.rand_u64() % 4 {
+                0 | 1 => {
+                    let x = (rng.rand_u64() % 1000) as i64;
+                    let h = heap.push((x, handles.len()));
+                    set.insert((x, handles.len()));
+                    handles.push(Some(h));
+                }
+                2 => {
+                    let i = (rng.rand_u64() % std::cmp::max(handles.len(), 1) as u64) as usize;
+                    if let Some(&(x, j)) = handles.get(i).and_then(|&h| h).and_then(|h| heap.get(h)) {
+                        let y = x - (rng.rand_u64() % 100) as i64;
+                        heap.decrease_key(handles[i].unwrap(), (y, j));
+                        set.remove(&(x, j));
+                        set.insert((y, j));
+                    }
+                }
+                _ => {
+                    let expected = set.iter().next().cloned();
+                    if let Some(e) = expected { set.remove(&e); }
+                    let got = heap.pop();
+                    assert_eq!(got, expected.map(|e| (handles[e.1].unwrap(), e)));
+                    if let Some(e) = expected { handles[e.1] = None; }
+                }
+            }
+            assert_eq!(heap.len(), set.len());
+            assert_eq!(heap.node.len(), heap.len() + heap.free.len());
+            assert_eq!(heap.peek(), set.iter().next());
+        }
+    }
+
+    #[test]
+    fn meld_test() {
+        let mut a = PairingHeap::new();
+        let mut b = PairingHeap::new();
+        for x in [5, 1, 9].iter() { a.push(*x); }
+        let hb = [b.push(7), b.push(3), b.push(8)];
+        a.pop();
+        b.pop();
+        let map = a.meld(b);
+        a.decrease_key(map(hb[2]), 0);
+        assert_eq!(a.get(map(hb[0])), Some(&7));
+        a.push(4);
+        assert_eq!(a.len(), 5);
+        assert_eq!(a.node.len(), 6);
+        let mut res = Vec::new();
+        while let Some((_, x)) = a.pop() { res.push(x); }
+        assert_eq!(res, vec![0, 4, 5, 7, 9]);
+        assert!(a.is_empty());
+    }
+}
@@ -2,6 +2,7 @@ use std::rc::Rc;
 
 pub struct Heap<T: Ord> {
     rank: usize,
+    size: usize,
     elem: Rc<T>,
     left: LeftistHeap<T>,
     right: LeftistHeap<T>,
@@ -11,6 +12,7 @@ impl<T: Ord> Heap<T> {
     fn new(x: Rc<T>) -> Self {
         Heap {
             rank: 1,
+            size: 1,
             elem: x,
             left: LeftistHeap::None,
             right: LeftistHeap::None,
@@ -19,9 +21,11 @@ impl<T: Ord> Heap<T> {
     fn meld(l: Rc<Self>, r: &LeftistHeap<T>) -> Self {
         let right = LeftistHeap::meld(&l.as_ref().right, &r);
         let left = l.as_ref().left.clone();
+        let size = left.len() + right.len() + 1;
         if left.rank() >= right.rank() {
             Heap {
                 rank: right.rank() + 1,
+                size,
                 elem: l.as_ref().elem.clone(),
                 left: left,
                 right: right,
@@ -30,6 +34,7 @@ impl<T: Ord> Heap<T> {
         else {
             Heap {
                 rank: left.rank() + 1,
+                size,
                 elem: l.as_ref().elem.clone(),
                 left: right,
                 right: left,
@@ -69,6 +74,15 @@ impl<T: Ord> LeftistHeap<T> {
             &LeftistHeap::Some(ref heap) => heap.as_ref().rank,
         }
     }
+    pub fn len(&self) -> usize {
+        match *self {
+            LeftistHeap::None => 0,
+            LeftistHeap::Some(ref heap) => heap.as_ref().size,
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
     pub fn meld(a: &Self, b: &Self) -> Self {
         match a {
             &LeftistHeap::None => b.clone(),
@@ -118,6 +132,9 @@ fn leftist_heap_test() {
     let h = vec[2].insert(2);
     vec.push(h);
     assert_eq!(vec[3].peek(), Some(&2));
+    assert_eq!(vec[3].len(), 3);
+    assert_eq!(vec[1].len(), 1);
+    assert!(vec[0].is_empty());
 
     let h = vec[2].insert(1);
     assert_eq!(h.peek(), Some(&1));
@@ -127,4 +144,5 @@ fn leftist_heap_test() {
     assert_eq!(h.peek(), Some(&0));
     let h = h.pop();
     assert_eq!(h.peek(), None);
+    assert_eq!(h.len(), 0);
 }
@@ -0,0 +1,77 @@
+use super::PriorityQueue;
+
+// the keys pushed must not be less than the last popped key
+pub struct RadixHeap<V> {
+    bucket: Vec<Vec<(u64, V)>>,
+    last: u64,
+    len: usize,
+}
+
+impl<V> Default for RadixHeap<V> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<V> RadixHeap<V> {
+    pub fn new() -> Self {
+        RadixHeap { bucket: (0..65).map(|_| Vec::new()).collect(), last: 0, len: 0 }
+    }
+
+    fn index(&self, key: u64) -> usize {
+        (64 - (key ^ self.last).leading_zeros()) as usize
+    }
+}
+
+impl<V> PriorityQueue<u64, V> for RadixHeap<V> {
+    fn push(&mut self, key: u64, val: V) {
+        assert!(self.last <= key);
+        let i = self.index(key);
+        self.bucket[i].push((key, val));
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<(u64, V)> {
+        if self.len == 0 { return None }
+        if self.bucket[0].is_empty() {
+            let i = self.bucket.iter().position(|b| !b.is_empty()).unwrap();
+            self.last = self.bucket[i].iter().map(|e| e.0).min().unwrap();
+            for e in std::mem::take(&mut self.bucket[i]) {
+                let j = self.index(e.0);
+                self.bucket[j].push(e);
+            }
+        }
+        self.len -= 1;
+        self.bucket[0].pop()
+    }
+
+    fn len(&self) -> usize { self.len }
+}
+
+#[cfg(test)]
+mod radix_heap_test {
+    use crate::data_structures::heap::PriorityQueue;
+    use crate::data_structures::heap::radix_heap::RadixHeap;
+    use crate::random::{ Random, Xorshift128 };
+    use std::collections::BinaryHeap;
+    use std::cmp::Reverse;
+
+    #[test]
+    fn monotone_random_test() {
+        let mut rng = Xorshift128::new(40);
+        let mut heap = RadixHeap::new();
+        let mut bin = BinaryHeap::new();
+        let mut last = 0;
+        for i in 0..5000usize {
+            if rng.rand_u64() % 3 == 2 {
+                let got = heap.pop();
+                assert_eq!(got.as_ref().map(|e| e.0), PriorityQueue::pop(&mut bin).map(|e: (u64, usize)| e.0));
+                if let Some((k, _)) = got { last = k; }
+            }
+            else {
+                let k = last + (rng.rand_u64() >> (rng.rand_u64() % 64));
+                heap.push(k, i);
+                bin.push(Reverse((k, i)));
+            }
+            assert_eq!(heap.len(), bin.len());
+        }
+    }
+}
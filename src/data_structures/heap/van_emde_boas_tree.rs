@@ -2,12 +2,36 @@ use std::collections::HashMap;
 
 pub trait VanEmdeBoasTree {
     fn new() -> Self;
-    fn universe_len() -> u64;
+    fn universe_len() -> u128;
     fn insert(&mut self, x: u64);
     fn erase(&mut self, x: u64);
     fn find(&self, x: u64) -> bool;
     fn any(&self) -> bool;
+    fn len(&self) -> usize;
+    fn min(&self) -> Option<u64>;
+    fn max(&self) -> Option<u64>;
+    // the smallest element not less than x
     fn lower_bound(&self, x: u64) -> Option<u64>;
+    // the largest element not greater than x
+    fn prev(&self, x: u64) -> Option<u64>;
+    fn is_empty(&self) -> bool { !self.any() }
+    fn iter(&self) -> Iter<'_, Self> where Self: Sized {
+        Iter { veb: self, next: self.min() }
+    }
+}
+
+pub struct Iter<'a, V> {
+    veb: &'a V,
+    next: Option<u64>,
+}
+
+impl<'a, V: VanEmdeBoasTree> Iterator for Iter<'a, V> {
+    type Item = u64;
+    fn next(&mut self) -> Option<u64> {
+        let x = self.next?;
+        self.next = x.checked_add(1).and_then(|y| self.veb.lower_bound(y));
+        Some(x)
+    }
 }
 
 pub struct VEBTree64 {
@@ -16,188 +40,184 @@ pub struct VEBTree64 {
 
 impl VanEmdeBoasTree for VEBTree64 {
     fn new() -> Self { VEBTree64 { flag: 0 } }
-    fn universe_len() -> u64 { 64 }
+    fn universe_len() -> u128 { 64 }
     fn insert(&mut self, x: u64) {
         self.flag |= 1u64 << x;
     }
     fn erase(&mut self, x: u64) {
-        self.flag &= !(1 << x);
+        self.flag &= !(1u64 << x);
     }
     fn find(&self, x: u64) -> bool {
-        0 < (self.flag & (1 << x))
+        x < 64 && 0 < (self.flag & (1 << x))
     }
     fn any(&self) -> bool {
         0 < self.flag
     }
+    fn len(&self) -> usize {
+        self.flag.count_ones() as usize
+    }
+    fn min(&self) -> Option<u64> {
+        if self.flag == 0 { None } else { Some(self.flag.trailing_zeros() as u64) }
+    }
+    fn max(&self) -> Option<u64> {
+        if self.flag == 0 { None } else { Some(63 - self.flag.leading_zeros() as u64) }
+    }
     fn lower_bound(&self, x: u64) -> Option<u64> {
-        println!("{:b}, {} = {:b}", self.flag, x, (self.flag & !((1 << x) - 1)));
-        println!("{}", (self.flag & !((1 << x) - 1)).trailing_zeros());
+        if x >= 64 { return None }
         match (self.flag & !((1 << x) - 1)).trailing_zeros() {
             64 => None,
             l => Some(l as u64),
         }
     }
+    fn prev(&self, x: u64) -> Option<u64> {
+        let x = std::cmp::min(x, 63);
+        match (self.flag & (!0 >> (63 - x))).leading_zeros() {
+            64 => None,
+            l => Some(63 - l as u64),
+        }
+    }
 }
 
-pub struct VEBTreeNode<T> {
-    summary: T,
-    cluster: Box<[T]>,
+// storage of the clusters of a node
+pub trait Clusters {
+    type Cluster: VanEmdeBoasTree;
+    fn with_len(n: usize) -> Self;
+    fn get(&self, i: u64) -> Option<&Self::Cluster>;
+    // allocates the cluster if it does not exist yet
+    fn get_mut(&mut self, i: u64) -> &mut Self::Cluster;
+    // called when the cluster becomes empty
+    fn release(&mut self, i: u64);
 }
 
-impl<T: VanEmdeBoasTree> VanEmdeBoasTree for VEBTreeNode<T> {
-    fn new() -> Self {
-        VEBTreeNode {
-            summary: T::new(),
-            cluster: (0..T::universe_len())
-                .map(|_| T::new())
-                .collect::<Vec<_>>()
-                .into_boxed_slice(),
-        }
-    }
-    fn universe_len() -> u64 { T::universe_len().pow(2) }
-    fn insert(&mut self, x: u64) {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
-        self.summary.insert(i);
-        self.cluster[i as usize].insert(x);
-    }
-    fn erase(&mut self, x: u64) {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
-        self.cluster[i as usize].erase(x);
-        if !self.cluster[i as usize].any() {
-            self.summary.erase(i);
-        }
+impl<T: VanEmdeBoasTree> Clusters for Box<[T]> {
+    type Cluster = T;
+    fn with_len(n: usize) -> Self {
+        (0..n).map(|_| T::new()).collect::<Vec<_>>().into_boxed_slice()
     }
-    fn find(&self, x: u64) -> bool {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
-        self.cluster[i as usize].find(x)
+    fn get(&self, i: u64) -> Option<&T> { self.as_ref().get(i as usize) }
+    fn get_mut(&mut self, i: u64) -> &mut T { &mut self[i as usize] }
+    fn release(&mut self, _: u64) {}
+}
+
+impl<T: VanEmdeBoasTree> Clusters for Box<[Option<Box<T>>]> {
+    type Cluster = T;
+    fn with_len(n: usize) -> Self {
+        (0..n).map(|_| None).collect::<Vec<_>>().into_boxed_slice()
     }
-    fn any(&self) -> bool {
-        self.summary.any()
+    fn get(&self, i: u64) -> Option<&T> {
+        self.as_ref().get(i as usize).and_then(|c| c.as_deref())
     }
-    fn lower_bound(&self, x: u64) -> Option<u64> {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
-        println!("{}, {}", i, x);
-        if self.summary.find(i) {
-            if let Some(ans) = self.cluster[i as usize].lower_bound(x) {
-                return Some(ans + i * T::universe_len());
-            }
-        }
-        if i + 1 < T::universe_len() {
-            self.summary
-                .lower_bound(i + 1)
-                .map_or(None, |l| {
-                    self.cluster[l as usize].lower_bound(0).map(|x| x + l * T::universe_len())
-                })
-        }
-        else { None }
+    fn get_mut(&mut self, i: u64) -> &mut T {
+        self[i as usize].get_or_insert_with(|| Box::new(T::new()))
     }
+    fn release(&mut self, i: u64) { self[i as usize] = None; }
 }
 
-pub struct VEBTreeDynNode<T> {
-    summary: T,
-    cluster: Box<[Option<Box<T>>]>,
+impl<T: VanEmdeBoasTree> Clusters for HashMap<u64, T> {
+    type Cluster = T;
+    fn with_len(_: usize) -> Self { HashMap::new() }
+    fn get(&self, i: u64) -> Option<&T> { HashMap::get(self, &i) }
+    fn get_mut(&mut self, i: u64) -> &mut T { self.entry(i).or_insert_with(T::new) }
+    fn release(&mut self, i: u64) { self.remove(&i); }
 }
 
-impl<T: VanEmdeBoasTree> VanEmdeBoasTree for VEBTreeDynNode<T> {
-    fn new() -> Self {
-        VEBTreeDynNode {
-            summary: T::new(),
-            cluster: (0..T::universe_len())
-                .map(|_| None)
-                .collect::<Vec<_>>()
-                .into_boxed_slice(),
-        }
-    }
-    fn universe_len() -> u64 { T::universe_len().pow(2) }
-    fn insert(&mut self, x: u64) {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
-        self.summary.insert(i);
-        self.cluster[i as usize].get_or_insert_with(|| Box::new(T::new())).insert(x);
-    }
-    fn erase(&mut self, x: u64) {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
-        self.cluster[i as usize].as_mut().map(|c| c.erase(x));
-        if !self.cluster[i as usize].as_ref().map(|c| c.any()).unwrap_or(true) {
-            self.summary.erase(i);
-        }
-    }
-    fn find(&self, x: u64) -> bool {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
-        self.cluster[i as usize].as_ref().map(|c| c.find(x)).unwrap_or(false)
-    }
-    fn any(&self) -> bool {
-        self.summary.any()
-    }
-    fn lower_bound(&self, x: u64) -> Option<u64> {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
-        println!("{}, {}", i, x);
-        if self.summary.find(i) {
-            if let Some(ans) = self.cluster[i as usize].as_ref().map_or(None, |c| c.lower_bound(x)) {
-                return Some(ans + i * T::universe_len());
-            }
-        }
-        if i + 1 < T::universe_len() {
-            self.summary
-                .lower_bound(i + 1)
-                .map_or(None, |l| {
-                    self.cluster[l as usize].as_ref().unwrap().lower_bound(0).map(|x| x + l * T::universe_len())
-                })
-        }
-        else { None }
-    }
+pub struct VEBNode<S, C> {
+    summary: S,
+    cluster: C,
+    len: usize,
 }
 
-pub struct VEBTreeHashNode<T> {
-    summary: T,
-    cluster: HashMap<u64, T>,
+impl<S: VanEmdeBoasTree, C: Clusters> VEBNode<S, C> {
+    fn split(x: u64) -> (u64, u64) {
+        let w = C::Cluster::universe_len() as u64;
+        (x / w, x % w)
+    }
+    fn join(i: u64, x: u64) -> u64 {
+        i * C::Cluster::universe_len() as u64 + x
+    }
 }
 
-impl<T: VanEmdeBoasTree> VanEmdeBoasTree for VEBTreeHashNode<T> {
+impl<S: VanEmdeBoasTree, C: Clusters> VanEmdeBoasTree for VEBNode<S, C> {
     fn new() -> Self {
-        VEBTreeHashNode {
-            summary: T::new(),
-            cluster: HashMap::new(),
+        VEBNode {
+            summary: S::new(),
+            cluster: C::with_len(S::universe_len() as usize),
+            len: 0,
         }
     }
-    fn universe_len() -> u64 { T::universe_len().pow(2) }
+    fn universe_len() -> u128 { S::universe_len() * C::Cluster::universe_len() }
     fn insert(&mut self, x: u64) {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
+        if self.find(x) { return }
+        let (i, x) = Self::split(x);
         self.summary.insert(i);
-        self.cluster.entry(i).or_insert_with(|| T::new()).insert(x);
+        self.cluster.get_mut(i).insert(x);
+        self.len += 1;
     }
     fn erase(&mut self, x: u64) {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
-        self.cluster.get_mut(&i).map(|c| c.erase(x));
-        if !self.cluster.get(&i).map(|c| c.any()).unwrap_or(true) {
+        if !self.find(x) { return }
+        let (i, x) = Self::split(x);
+        let c = self.cluster.get_mut(i);
+        c.erase(x);
+        if !c.any() {
+            self.cluster.release(i);
             self.summary.erase(i);
         }
+        self.len -= 1;
     }
     fn find(&self, x: u64) -> bool {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
-        self.cluster.get(&i).map(|c| c.find(x)).unwrap_or(false)
+        let (i, x) = Self::split(x);
+        self.summary.find(i) && self.cluster.get(i).unwrap().find(x)
     }
     fn any(&self) -> bool {
         self.summary.any()
     }
+    fn len(&self) -> usize { self.len }
+    fn min(&self) -> Option<u64> {
+        let i = self.summary.min()?;
+        self.cluster.get(i).unwrap().min().map(|x| Self::join(i, x))
+    }
+    fn max(&self) -> Option<u64> {
+        let i = self.summary.max()?;
+        self.cluster.get(i).unwrap().max().map(|x| Self::join(i, x))
+    }
     fn lower_bound(&self, x: u64) -> Option<u64> {
-        let (i, x) = (x / T::universe_len(), x % T::universe_len());
-        println!("{}, {}", i, x);
+        let (i, x) = Self::split(x);
+        if i as u128 >= S::universe_len() { return None }
         if self.summary.find(i) {
-            if let Some(ans) = self.cluster.get(&i).map_or(None, |c| c.lower_bound(x)) {
-                return Some(ans + i * T::universe_len());
+            if let Some(ans) = self.cluster.get(i).unwrap().lower_bound(x) {
+                return Some(Self::join(i, ans));
             }
         }
-        if i + 1 < T::universe_len() {
-            self.summary
-                .lower_bound(i + 1)
-                .map_or(None, |l| {
-                    self.cluster.get(&l).unwrap().lower_bound(0).map(|x| x + l * T::universe_len())
-                })
+        let j = self.summary.lower_bound(i + 1)?;
+        self.cluster.get(j).unwrap().min().map(|x| Self::join(j, x))
+    }
+    fn prev(&self, x: u64) -> Option<u64> {
+        if x as u128 >= Self::universe_len() { return self.max() }
+        let (i, x) = Self::split(x);
+        if self.summary.find(i) {
+            if let Some(ans) = self.cluster.get(i).unwrap().prev(x) {
+                return Some(Self::join(i, ans));
+            }
         }
-        else { None }
+        if i == 0 { return None }
+        let j = self.summary.prev(i - 1)?;
+        self.cluster.get(j).unwrap().max().map(|x| Self::join(j, x))
     }
 }
 
+// the clusters are allocated eagerly
+pub type VEBTreeNode<T, S = T> = VEBNode<S, Box<[T]>>;
+// the clusters are allocated when they become non-empty
+pub type VEBTreeDynNode<T, S = T> = VEBNode<S, Box<[Option<Box<T>>]>>;
+pub type VEBTreeHashNode<T, S = T> = VEBNode<S, HashMap<u64, T>>;
+
+// 64-ary sets whose universes are at least 2^k
+pub type VEBSet12 = VEBTreeNode<VEBTree64>;
+pub type VEBSet18 = VEBTreeNode<VEBSet12, VEBTree64>;
+pub type VEBSet24 = VEBTreeNode<VEBSet18, VEBTree64>;
+pub type VEBSet32 = VEBTreeDynNode<VEBTreeDynNode<VEBSet18, VEBTree64>, VEBSet12>;
+pub type VEBSet64 = VEBTreeHashNode<VEBSet32, VEBSet32>;
+
 #[test]
 fn veb_find_test() {
     let mut veb = VEBTreeNode::<VEBTree64>::new();
@@ -263,3 +283,68 @@ fn veb_erase_test() {
     assert_eq!(veb.lower_bound(32), None);
     assert_eq!(veb.lower_bound(64 * 32 + 1), None);
 }
+
+#[test]
+fn veb_prev_test() {
+    let mut veb = VEBTreeDynNode::<VEBTree64>::new();
+    for &x in [2, 5, 14, 64 * 32, 64 * 63 + 63].iter() { veb.insert(x); }
+    assert_eq!(veb.len(), 5);
+    assert_eq!(veb.min(), Some(2));
+    assert_eq!(veb.max(), Some(64 * 63 + 63));
+    assert_eq!(veb.prev(1), None);
+    assert_eq!(veb.prev(2), Some(2));
+    assert_eq!(veb.prev(13), Some(5));
+    assert_eq!(veb.prev(64 * 32 - 1), Some(14));
+    assert_eq!(veb.prev(1 << 20), Some(64 * 63 + 63));
+    veb.erase(64 * 63 + 63);
+    veb.erase(64 * 63 + 63);
+    assert_eq!(veb.len(), 4);
+    assert_eq!(veb.max(), Some(64 * 32));
+    assert_eq!(veb.iter().collect::<Vec<_>>(), vec![2, 5, 14, 64 * 32]);
+}
+
+#[cfg(test)]
+fn veb_random_test<V: VanEmdeBoasTree>(seed: u64, mask: u64) {
+    use crate::random::{ Random, Xorshift128 };
+    use std::collections::BTreeSet;
+    let mut rng = Xorshift128::new(seed);
+    let mut veb = V::new();
+    let mut set = BTreeSet::new();
+    for _ in 0..3000 {
+        let x = rng.rand_u64() & mask;
+        match rng.rand_u64() % 4 {
+            0 => {
+                veb.insert(x);
+                set.insert(x);
+            }
+            1 => {
+                veb.erase(x);
+                set.remove(&x);
+            }
+            2 => {
+                let y = set.iter().nth((rng.rand_u64() % (set.len() as u64 + 1)) as usize).cloned().unwrap_or(x);
+                veb.erase(y);
+                set.remove(&y);
+            }
+            _ => {
+                assert_eq!(veb.find(x), set.contains(&x));
+                assert_eq!(veb.lower_bound(x), set.range(x..).next().cloned());
+                assert_eq!(veb.prev(x), set.range(..=x).next_back().cloned());
+            }
+        }
+        assert_eq!(veb.len(), set.len());
+        assert_eq!(veb.min(), set.iter().next().cloned());
+        assert_eq!(veb.max(), set.iter().next_back().cloned());
+    }
+    assert!(veb.iter().eq(set.iter().cloned()));
+}
+
+#[test]
+fn veb_random_sets_test() {
+    veb_random_test::<VEBSet12>(39, (1 << 12) - 1);
+    veb_random_test::<VEBSet18>(40, (1 << 18) - 1);
+    veb_random_test::<VEBSet24>(41, (1 << 24) - 1);
+    veb_random_test::<VEBSet32>(42, (1 << 32) - 1);
+    veb_random_test::<VEBSet64>(43, !0);
+    veb_random_test::<VEBSet64>(44, 0xff00_0000_0000_00ff);
+}
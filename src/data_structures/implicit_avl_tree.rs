@@ -1,4 +1,2 @@
-#[macro_use]
-pub mod node_macro;
 pub mod node_traits;
 pub mod implicit_avl_tree;
@@ -1,6 +1,9 @@
 use crate::data_structures::implicit_avl_tree::node_traits::*;
 use std::rc::Rc;
 use std::cell::{ RefCell, Ref };
+use crate::data_structures::node_seq::bounds;
+
+use std::ops::RangeBounds;
 
 pub trait AVLNode: Node + HeightNode {}
 impl<N: Node + HeightNode> AVLNode for N {}
@@ -234,21 +237,136 @@ impl<N: AVLNode> ImplicitAVLTree<N> {
     where N: KeySearch<K>, N::L: KeySearch<K> {
         at(self.root.clone().unwrap(), pos).map(|n| ImplicitAVLIterator::<N> { node: n })
     }
-
-    pub fn debug(&self) where <N::L as Leaf>::Value: std::fmt::Debug {
-        match self.root {
-            Some(ref root) => root.debug(""),
-            None => println!("None"),
-        }
-    }
 }
 impl<N: AVLNode + SizeNode> ImplicitAVLTree<N> {
     pub fn size(&self) -> usize  {
         self.root.as_ref().map(|r| r.size()).unwrap_or(0)
     }
+    pub fn range<R: RangeBounds<usize>>(&self, ran: R) -> Iter<N> {
+        let (l, r) = bounds(ran, self.size());
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        if l < r {
+            let root = self.root.clone().unwrap();
+            let (mut x, mut pos) = (root.clone(), l);
+            while let Child::Node(n) = x {
+                n.borrow_mut().push();
+                let ls = n.borrow().child(0).size();
+                x = if pos < ls {
+                    front.push(n.borrow().child(1).clone());
+                    n.borrow().child(0).clone()
+                }
+                else {
+                    pos -= ls;
+                    n.borrow().child(1).clone()
+                };
+            }
+            front.push(x);
+            let (mut x, mut pos) = (root, r - 1);
+            while let Child::Node(n) = x {
+                n.borrow_mut().push();
+                let ls = n.borrow().child(0).size();
+                x = if pos < ls {
+                    n.borrow().child(0).clone()
+                }
+                else {
+                    back.push(n.borrow().child(0).clone());
+                    pos -= ls;
+                    n.borrow().child(1).clone()
+                };
+            }
+            back.push(x);
+        }
+        Iter { front, back, len: r - l }
+    }
+    pub fn iter(&self) -> Iter<N> {
+        self.range(..)
+    }
+}
+
+// builds a balanced tree from the leaves in linear time
+impl<N: AVLNode> std::iter::FromIterator<N::L> for ImplicitAVLTree<N> {
+    fn from_iter<I: IntoIterator<Item = N::L>>(iter: I) -> Self {
+        fn build<N: AVLNode>(leaves: &mut std::vec::IntoIter<N::L>, n: usize) -> Child<N> {
+            if n == 1 { return Child::Leaf(Rc::new(RefCell::new(leaves.next().unwrap()))) }
+            let l = build(leaves, n >> 1);
+            let r = build(leaves, n - (n >> 1));
+            let x = Rc::new(RefCell::new(N::new(l.clone(), r.clone())));
+            l.replace_parent(Some(x.clone()));
+            r.replace_parent(Some(x.clone()));
+            Child::Node(x)
+        }
+        let leaves = iter.into_iter().collect::<Vec<_>>();
+        let n = leaves.len();
+        ImplicitAVLTree { root: if n == 0 { None } else { Some(build(&mut leaves.into_iter(), n)) } }
+    }
+}
+
+impl<N: AVLNode + SizeNode> IntoIterator for &ImplicitAVLTree<N> {
+    type Item = ImplicitAVLIterator<N>;
+    type IntoIter = Iter<N>;
+    fn into_iter(self) -> Iter<N> { self.iter() }
+}
+
+impl<N: AVLNode + SizeNode> IntoIterator for ImplicitAVLTree<N> {
+    type Item = ImplicitAVLIterator<N>;
+    type IntoIter = Iter<N>;
+    fn into_iter(self) -> Iter<N> { self.iter() }
+}
+
+impl<N: AVLNode + SizeNode> std::fmt::Debug for ImplicitAVLTree<N> where <N::L as Leaf>::Value: std::fmt::Debug {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut list = f.debug_list();
+        for it in self.iter() { list.entry(&*it.val()); }
+        list.finish()
+    }
+}
+
+// yields the handles of the leaves in order
+pub struct Iter<N: AVLNode> {
+    front: Vec<Child<N>>,
+    back: Vec<Child<N>>,
+    len: usize,
+}
+
+impl<N: AVLNode> Iterator for Iter<N> {
+    type Item = ImplicitAVLIterator<N>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 { return None }
+        self.len -= 1;
+        let mut x = self.front.pop().unwrap();
+        while let Child::Node(n) = x {
+            n.borrow_mut().push();
+            self.front.push(n.borrow().child(1).clone());
+            x = n.borrow().child(0).clone();
+        }
+        match x {
+            Child::Leaf(node) => Some(ImplicitAVLIterator { node }),
+            _ => unreachable!(),
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.len, Some(self.len)) }
+}
 
+impl<N: AVLNode> DoubleEndedIterator for Iter<N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 { return None }
+        self.len -= 1;
+        let mut x = self.back.pop().unwrap();
+        while let Child::Node(n) = x {
+            n.borrow_mut().push();
+            self.back.push(n.borrow().child(0).clone());
+            x = n.borrow().child(1).clone();
+        }
+        match x {
+            Child::Leaf(node) => Some(ImplicitAVLIterator { node }),
+            _ => unreachable!(),
+        }
+    }
 }
 
+impl<N: AVLNode> ExactSizeIterator for Iter<N> {}
+
 pub struct ImplicitAVLValue<'a, N: AVLNode> {
     n: Ref<'a, N::L>,
 }
@@ -277,7 +395,7 @@ mod implicitavl_normal_test {
     use crate::data_structures::implicit_avl_tree::node_traits::*;
     use crate::data_structures::implicit_avl_tree::implicit_avl_tree::ImplicitAVLTree;
 
-    def_implicit_node! { NodeTest, LeafTest, usize; size, height, }
+    def_node! { implicit NodeTest, LeafTest, usize; size, height, }
     
     #[test]
     fn node_macro_test() {
@@ -304,3 +422,34 @@ mod implicitavl_normal_test {
         }
     }
 }
+
+#[cfg(test)]
+mod implicitavl_iter_test {
+    use crate::data_structures::implicit_avl_tree::node_traits::*;
+    use crate::data_structures::implicit_avl_tree::implicit_avl_tree::ImplicitAVLTree;
+
+    def_node! { implicit NodeTest, LeafTest, usize; size, height, }
+
+    #[test]
+    fn iter_test() {
+        for n in 0..12 {
+            let arr = (0..n).map(LeafTest::new).collect::<ImplicitAVLTree<NodeTest>>();
+            assert_eq!(arr.size(), n);
+            assert_eq!(format!("{:?}", arr), format!("{:?}", (0..n).collect::<Vec<_>>()));
+            for l in 0..n + 1 {
+                for r in l..n + 1 {
+                    let vals = arr.range(l..r).map(|it| *it.val()).collect::<Vec<_>>();
+                    assert_eq!(vals, (l..r).collect::<Vec<_>>());
+                    let vals = arr.range(l..r).rev().map(|it| *it.val()).collect::<Vec<_>>();
+                    assert_eq!(vals, (l..r).rev().collect::<Vec<_>>());
+                }
+            }
+            let iter = if n > 3 { arr.at(Position(3)) } else { None };
+            let (l, r) = arr.split(&iter);
+            let arr = r.merge(l);
+            let vals = (&arr).into_iter().map(|it| *it.val()).collect::<Vec<_>>();
+            let expected = if n > 3 { (3..n).chain(0..3).collect::<Vec<_>>() } else { (0..n).collect() };
+            assert_eq!(vals, expected);
+        }
+    }
+}
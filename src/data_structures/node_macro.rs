@@ -1,57 +1,104 @@
 #[macro_export]
-macro_rules! build_node_struct {
-    ($node:ident, $val_type:ty, $($elem:ident: $t:ty,)*) => {
-        struct $node {
-            val: $val_type,
-            child: [Link<$node>; 2],
-            $($elem: $t),*
-        }
-    }
+macro_rules! node_fields {
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $t:ty,)* | ) => {
+        $cb! { $($args)* $($e: $t,)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $t:ty,)* | size, $($elem:tt)*) => {
+        node_fields! { $cb { $($args)* } $val_type | $($e: $t,)* size: usize, | $($elem)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $t:ty,)* | height, $($elem:tt)*) => {
+        node_fields! { $cb { $($args)* } $val_type | $($e: $t,)* height: isize, | $($elem)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $t:ty,)* | fold, $($elem:tt)*) => {
+        node_fields! { $cb { $($args)* } $val_type | $($e: $t,)* fold: $val_type, | $($elem)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $t:ty,)* | rev, $($elem:tt)*) => {
+        node_fields! { $cb { $($args)* } $val_type | $($e: $t,)* rev: bool, | $($elem)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $t:ty,)* | priority, $($elem:tt)*) => {
+        node_fields! { $cb { $($args)* } $val_type | $($e: $t,)* priority: u64, | $($elem)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $t:ty,)* | lazy($lazy:ty), $($elem:tt)*) => {
+        node_fields! { $cb { $($args)* } $val_type | $($e: $t,)* lazy: Option<$lazy>, | $($elem)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $t:ty,)* | field($f:ident : $ft:ty = $init:expr), $($elem:tt)*) => {
+        node_fields! { $cb { $($args)* } $val_type | $($e: $t,)* $f: $ft, | $($elem)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $t:ty,)* | aug($f:ident : $ft:ty = $init:expr, $fix:expr), $($elem:tt)*) => {
+        node_fields! { $cb { $($args)* } $val_type | $($e: $t,)* $f: $ft, | $($elem)* }
+    };
 }
 
 #[macro_export]
-macro_rules! define_node {
-    ($node:ident, $val_type:ty | $($e:ident : $t:ty,)* | ) => {
-        build_node_struct! { $node, $val_type, $($e: $t,)* }
+macro_rules! node_inits {
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $v:expr,)* | ) => {
+        $cb! { $($args)* $($e: $v,)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $v:expr,)* | size, $($elem:tt)*) => {
+        node_inits! { $cb { $($args)* } $val_type | $($e: $v,)* size: 1, | $($elem)* }
     };
-    ($node:ident, $val_type:ty | $($e:ident : $t:ty,)* | size, $($elem:tt)*) => {
-        define_node! { $node, $val_type | $($e: $t,)* size: usize, | $($elem)* }
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $v:expr,)* | height, $($elem:tt)*) => {
+        node_inits! { $cb { $($args)* } $val_type | $($e: $v,)* height: 1, | $($elem)* }
     };
-    ($node:ident, $val_type:ty | $($e:ident : $t:ty,)* | height, $($elem:tt)*) => {
-        define_node! { $node, $val_type | $($e: $t,)* height: isize, | $($elem)* }
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $v:expr,)* | fold, $($elem:tt)*) => {
+        node_inits! { $cb { $($args)* } $val_type | $($e: $v,)* fold: <$val_type as Unital>::identity(), | $($elem)* }
     };
-    ($node:ident, $val_type:ty | $($e:ident : $t:ty,)* | fold, $($elem:tt)*) => {
-        define_node! { $node, $val_type | $($e: $t,)* fold: $val_type, | $($elem)* }
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $v:expr,)* | rev, $($elem:tt)*) => {
+        node_inits! { $cb { $($args)* } $val_type | $($e: $v,)* rev: false, | $($elem)* }
     };
-    ($node:ident, $val_type:ty | $($e:ident : $t:ty,)* | rev, $($elem:tt)*) => {
-        define_node! { $node, $val_type | $($e: $t,)* rev: bool, | $($elem)* }
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $v:expr,)* | priority, $($elem:tt)*) => {
+        node_inits! { $cb { $($args)* } $val_type | $($e: $v,)* priority: 0, | $($elem)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $v:expr,)* | lazy($lazy:ty), $($elem:tt)*) => {
+        node_inits! { $cb { $($args)* } $val_type | $($e: $v,)* lazy: None, | $($elem)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $v:expr,)* | field($f:ident : $ft:ty = $init:expr), $($elem:tt)*) => {
+        node_inits! { $cb { $($args)* } $val_type | $($e: $v,)* $f: $init, | $($elem)* }
+    };
+    ($cb:ident { $($args:tt)* } $val_type:ty | $($e:ident : $v:expr,)* | aug($f:ident : $ft:ty = $init:expr, $fix:expr), $($elem:tt)*) => {
+        node_inits! { $cb { $($args)* } $val_type | $($e: $v,)* $f: $init, | $($elem)* }
     };
 }
 
+#[macro_export]
+macro_rules! build_node_struct {
+    ($vis:vis $node:ident [$($g:tt)*], $val_type:ty, $($key:ident: $key_type:ty,)? | $($elem:ident: $t:ty,)*) => {
+        $vis struct $node<$($g)*> {
+            $($key: $key_type,)?
+            val: $val_type,
+            child: [Link<Self>; 2],
+            $($elem: $t),*
+        }
+    }
+}
 
 #[macro_export]
 macro_rules! impl_node_new {
-    ($node:ident, $val_type:ty, $($e:ident : $v: expr,)*) => {
-        impl $node {
-            fn new(val: $val_type) -> Self {
+    ($vis:vis [$($g:tt)*] $node:ty, $val_type:ty | $($e:ident : $v:expr,)*) => {
+        impl<$($g)*> $node {
+            $vis fn new(val: $val_type) -> Self {
                 Self {
-                    val: val,
+                    val,
                     child: [Link::None, Link::None],
                     $($e: $v),*
                 }
             }
         }
+        impl<$($g)*> ValueNode for $node {
+            fn from_value(val: $val_type) -> Self { Self::new(val) }
+            fn into_value(self) -> $val_type { self.val }
+        }
     }
 }
 
 #[macro_export]
 macro_rules! impl_node_key_new {
-    ($node:ident, $val_type:ty, $key_type:ty, $($e:ident : $v: expr,)*) => {
-        impl $node {
-            fn new(key: $key_type, val: $val_type) -> Self {
+    ($vis:vis [$($g:tt)*] $node:ty, $val_type:ty, $key_type:ty | $($e:ident : $v:expr,)*) => {
+        impl<$($g)*> $node {
+            $vis fn new(key: $key_type, val: $val_type) -> Self {
                 Self {
-                    key: key,
-                    val: val,
+                    key,
+                    val,
                     child: [Link::None, Link::None],
                     $($e: $v),*
                 }
@@ -60,175 +107,306 @@ macro_rules! impl_node_key_new {
     }
 }
 
-#[macro_export]
-macro_rules! impl_node_elem {
-    ($node:ident, $val_type:ty | off | $($e:ident : $v:expr,)* | ) => {
-        impl_node_new! { $node, $val_type, $($e: $v,)* }
-    };
-    ($node:ident, $val_type:ty | $key_type:ty | $($e:ident : $v:expr,)* | ) => {
-        impl_node_key_new! { $node, $val_type, $key_type, $($e: $v,)* }
-    };
-    ($node:ident, $val_type:ty | $s:tt | $($e:ident : $v:expr,)* | size, $($elem:tt)*) => {
-        impl_node_elem! { $node, $val_type | $s | $($e: $v,)* size: 1, | $($elem)* }
-    };
-    ($node:ident, $val_type:ty | $s:tt | $($e:ident : $v:expr,)* | height, $($elem:tt)*) => {
-        impl_node_elem! { $node, $val_type | $s | $($e: $v,)* height: 1, | $($elem)* }
-    };
-    ($node:ident, $val_type:ty | $s:tt | $($e:ident : $v:expr,)* | fold, $($elem:tt)*) => {
-        impl_node_elem! { $node, $val_type | $s | $($e: $v,)* fold: <$val_type as Unital>::identity(), | $($elem)* }
-    };
-    ($node:ident, $val_type:ty | $s:tt | $($e:ident : $v:expr,)* | rev, $($elem:tt)*) => {
-        impl_node_elem! { $node, $val_type | $s | $($e: $v,)* rev: false, | $($elem)* }
-    };
-}
-
 #[macro_export]
 macro_rules! impl_node_trait {
-    ($node:ident, $val_type:ty, $($elem:tt)* ) => {
-        impl Node for $node {
+    ([$($g:tt)*] $node:ty, $val_type:ty, $($elem:tt)* ) => {
+        impl<$($g)*> Node for $node {
             type Value = $val_type;
             fn push(&mut self) {
-                impl_push! { self, $val_type, $($elem)* }
+                impl_push! { boxed, self, $val_type, $($elem)* }
             }
             fn fix(&mut self) {
-                impl_fix! { self, $val_type, $($elem)* }
+                impl_fix! { boxed, self, $val_type, $($elem)* }
             }
-            fn child(&self, dir: usize) -> &Link<Self> { &self.child[dir] } 
-            fn child_mut(&mut self, dir: usize) -> &mut Link<Self> { &mut self.child[dir] } 
+            fn child(&self, dir: usize) -> &Link<Self> { &self.child[dir] }
+            fn child_mut(&mut self, dir: usize) -> &mut Link<Self> { &mut self.child[dir] }
             fn replace(&mut self, dir: usize, node: Link<Self>) -> Link<Self> {
-                let nn = std::mem::replace(&mut self.child[dir], node);
-                nn
+                std::mem::replace(&mut self.child[dir], node)
             }
             fn value(&self) -> &Self::Value { &self.val }
             fn value_mut(&mut self) -> &mut Self::Value { &mut self.val }
         }
-    } 
+    }
 }
 
+// `$kind` is `boxed` for `def_node!` nodes and `implicit` for the internal nodes of implicit trees
 #[macro_export]
 macro_rules! impl_push {
-    ($mself:expr, $val_type:ty, ) => {};
-    ($mself:expr, $val_type:ty, rev, $($elem:tt)*) => {
+    (@reverse boxed, $mself:expr, $dir:expr) => {
+        if let Some(ch) = $mself.child_mut($dir).as_mut() {
+            ch.reverse();
+        }
+    };
+    (@reverse implicit, $mself:expr, $dir:expr) => {
+        if let Child::Node(ref ch) = *$mself.child($dir) {
+            ch.borrow_mut().reverse();
+        }
+    };
+    ($kind:ident, $mself:expr, $val_type:ty, ) => {};
+    ($kind:ident, $mself:expr, $val_type:ty, rev, $($elem:tt)*) => {
         if $mself.rev {
             $mself.child.swap(0, 1);
+            impl_push!(@reverse $kind, $mself, 0);
+            impl_push!(@reverse $kind, $mself, 1);
+        }
+        $mself.rev = false;
+        impl_push! { $kind, $mself, $val_type, $($elem)* }
+    };
+    (implicit, $mself:expr, $val_type:ty, lazy($lazy:ty), $($elem:tt)*) => {
+        compile_error!("implicit nodes do not support lazy(..): ImplicitAVLTree cannot apply actions");
+    };
+    (boxed, $mself:expr, $val_type:ty, lazy($lazy:ty), $($elem:tt)*) => {
+        if let Some(e) = $mself.lazy.take() {
             if let Some(ch) = $mself.child_mut(0).as_mut() {
-                ch.reverse();
+                EffectNode::<$lazy>::effect(&mut **ch, &e);
             }
             if let Some(ch) = $mself.child_mut(1).as_mut() {
-                ch.reverse();
+                EffectNode::<$lazy>::effect(&mut **ch, &e);
             }
         }
-        $mself.rev = false;
-        impl_push! { $mself, $val_type, $($elem)* }
+        impl_push! { boxed, $mself, $val_type, $($elem)* }
     };
-    ($mself:expr, $val_type:ty, $head:tt, $($elem:tt)*) => {
-        impl_push! { $mself, $val_type, $($elem)* }
+    ($kind:ident, $mself:expr, $val_type:ty, $head:ident ($($args:tt)*), $($elem:tt)*) => {
+        impl_push! { $kind, $mself, $val_type, $($elem)* }
+    };
+    ($kind:ident, $mself:expr, $val_type:ty, $head:tt, $($elem:tt)*) => {
+        impl_push! { $kind, $mself, $val_type, $($elem)* }
     };
 }
 
+// an implicit node holds no value of its own; its leaves do
 #[macro_export]
 macro_rules! impl_fix {
-    ($mself:expr, $val_type:ty, ) => {};
-    ($mself:expr, $val_type:ty, size, $($elem:tt)*) => {
-        $mself.size = $mself.child[0].size() + $mself.child[1].size() + 1;
-        impl_fix! { $mself, $val_type, $($elem)* }
+    (@own boxed) => { 1 };
+    (@own implicit) => { 0 };
+    (@fold boxed, $mself:expr, $val_type:ty) => {
+        $mself.child[0].as_ref().map(|ch| ch.fold()).unwrap_or(<$val_type>::identity())
+            .op(&$mself.val)
+            .op(&$mself.child[1].as_ref().map(|ch| ch.fold()).unwrap_or(<$val_type>::identity()))
+    };
+    (@fold implicit, $mself:expr, $val_type:ty) => {
+        $mself.child(0).fold_child().op(&$mself.child(1).fold_child())
     };
-    ($mself:expr, $val_type:ty, height, $($elem:tt)*) => {
+    ($kind:ident, $mself:expr, $val_type:ty, ) => {};
+    ($kind:ident, $mself:expr, $val_type:ty, size, $($elem:tt)*) => {
+        $mself.size = $mself.child[0].size() + $mself.child[1].size() + impl_fix!(@own $kind);
+        impl_fix! { $kind, $mself, $val_type, $($elem)* }
+    };
+    ($kind:ident, $mself:expr, $val_type:ty, height, $($elem:tt)*) => {
         $mself.height = std::cmp::max($mself.child[0].height(), $mself.child[1].height()) + 1;
-        impl_fix! { $mself, $val_type, $($elem)* }
+        impl_fix! { $kind, $mself, $val_type, $($elem)* }
     };
-    ($mself:expr, $val_type:ty, fold, $($elem:tt)*) => {
-        $mself.fold = $mself.child[0].as_ref().map(|ch| ch.fold()).unwrap_or(<$val_type>::identity())
-            .op(&$mself.val)
-            .op(&$mself.child[1].as_ref().map(|ch| ch.fold()).unwrap_or(<$val_type>::identity()));
-        impl_fix! { $mself, $val_type, $($elem)* }
+    ($kind:ident, $mself:expr, $val_type:ty, fold, $($elem:tt)*) => {
+        $mself.fold = impl_fix!(@fold $kind, $mself, $val_type);
+        impl_fix! { $kind, $mself, $val_type, $($elem)* }
     };
-    ($mself:expr, $val_type:ty, $head:tt, $($elem:tt)*) => {
-        impl_fix! { $mself, $val_type, $($elem)* }
+    ($kind:ident, $mself:expr, $val_type:ty, aug($f:ident : $ft:ty = $init:expr, $fix:expr), $($elem:tt)*) => {
+        $mself.$f = ($fix)(&*$mself);
+        impl_fix! { $kind, $mself, $val_type, $($elem)* }
+    };
+    ($kind:ident, $mself:expr, $val_type:ty, $head:ident ($($args:tt)*), $($elem:tt)*) => {
+        impl_fix! { $kind, $mself, $val_type, $($elem)* }
+    };
+    ($kind:ident, $mself:expr, $val_type:ty, $head:tt, $($elem:tt)*) => {
+        impl_fix! { $kind, $mself, $val_type, $($elem)* }
     };
 }
 
 #[macro_export]
 macro_rules! impl_rev_trait {
-    ($node:ident, $val_type:ty, ) => {};
-    ($node:ident, $val_type:ty, rev, $($tail:tt)*) => {
-        impl ReversibleNode for $node {
+    ([$($g:tt)*] $node:ty, $val_type:ty | off | off | ) => {};
+    ([$($g:tt)*] $node:ty, $val_type:ty | off | on | ) => {};
+    ([$($g:tt)*] $node:ty, $val_type:ty | on | off | ) => {
+        impl<$($g)*> ReversibleNode for $node {
+            fn reverse(&mut self) {
+                self.rev ^= true;
+            }
+        }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty | on | on | ) => {
+        impl<$($g)*> ReversibleNode for $node {
             fn reverse(&mut self) {
                 self.rev ^= true;
+                self.fold = self.fold.reverse();
             }
         }
     };
-    ($node:ident, $val_type:ty, $head:tt, $($elem:tt)*) => {
-        impl_rev_trait! { $node, $val_type, $($elem)* }
+    ([$($g:tt)*] $node:ty, $val_type:ty | $r:tt | $f:tt | rev, $($tail:tt)*) => {
+        impl_rev_trait! { [$($g)*] $node, $val_type | on | $f | $($tail)* }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty | $r:tt | $f:tt | fold, $($tail:tt)*) => {
+        impl_rev_trait! { [$($g)*] $node, $val_type | $r | on | $($tail)* }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty | $r:tt | $f:tt | $head:ident ($($args:tt)*), $($tail:tt)*) => {
+        impl_rev_trait! { [$($g)*] $node, $val_type | $r | $f | $($tail)* }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty | $r:tt | $f:tt | $head:tt, $($tail:tt)*) => {
+        impl_rev_trait! { [$($g)*] $node, $val_type | $r | $f | $($tail)* }
     };
 }
+
 #[macro_export]
-macro_rules! impl_rev2_trait {
-    ($node:ident, $val_type:ty | off | off | ) => {};
-    ($node:ident, $val_type:ty | off | on | ) => {};
-    ($node:ident, $val_type:ty | on | off | ) => {
-        impl ReversibleNode for $node {
-            fn reverse(&mut self) {
-                self.rev ^= true;
+macro_rules! impl_effect_trait {
+    ([$($g:tt)*] $node:ty, $val_type:ty | off | $f:tt | ) => {};
+    ([$($g:tt)*] $node:ty, $val_type:ty | [$lazy:ty] | $f:tt | ) => {
+        impl<$($g)*> EffectNode<$lazy> for $node {
+            fn effect(&mut self, e: &$lazy) {
+                self.val = self.val.effect(e);
+                impl_effect_fold! { self, e, $f }
+                self.lazy = Some(match self.lazy.take() {
+                    Some(l) => l.op(e),
+                    None => e.clone(),
+                });
             }
         }
     };
-    ($node:ident, $val_type:ty | on | on | ) => {
-        impl ReversibleNode for $node {
-            fn reverse(&mut self) {
-                self.rev ^= true;
-                self.fold = self.fold.reverse();
+    ([$($g:tt)*] $node:ty, $val_type:ty | $l:tt | $f:tt | lazy($lazy:ty), $($tail:tt)*) => {
+        impl_effect_trait! { [$($g)*] $node, $val_type | [$lazy] | $f | $($tail)* }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty | $l:tt | $f:tt | fold, $($tail:tt)*) => {
+        impl_effect_trait! { [$($g)*] $node, $val_type | $l | on | $($tail)* }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty | $l:tt | $f:tt | $head:ident ($($args:tt)*), $($tail:tt)*) => {
+        impl_effect_trait! { [$($g)*] $node, $val_type | $l | $f | $($tail)* }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty | $l:tt | $f:tt | $head:tt, $($tail:tt)*) => {
+        impl_effect_trait! { [$($g)*] $node, $val_type | $l | $f | $($tail)* }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_pending_trait {
+    ([$($g:tt)*] $node:ty, $val_type:ty | $r:tt | $l:tt | ) => {
+        impl<$($g)*> PendingNode for $node {
+            type Pending = (bool, Option<impl_pending_trait!(@lazy_type $l)>);
+            fn child_pending(&self, above: &Self::Pending) -> (Self::Pending, bool) {
+                let rev = above.0 ^ impl_pending_trait!(@rev self, $r);
+                ((rev, impl_pending_trait!(@lazy self, above, $l)), rev)
+            }
+            fn with_pushed_value<R, F: FnOnce(&Self::Value) -> R>(&self, above: &Self::Pending, f: F) -> R {
+                impl_pending_trait!(@value self, above, f, $l)
             }
         }
     };
-    ($node:ident, $val_type:ty | $r:tt | $f:tt | rev, $($tail:tt)*) => {
-        impl_rev2_trait! { $node, $val_type | on | $f | $($tail)* }
+    (@lazy_type off) => { () };
+    (@lazy_type [$lazy:ty]) => { $lazy };
+    (@rev $mself:expr, off) => { false };
+    (@rev $mself:expr, on) => { $mself.rev };
+    (@lazy $mself:expr, $above:expr, off) => { None };
+    (@lazy $mself:expr, $above:expr, [$lazy:ty]) => {
+        match ($mself.lazy.as_ref(), $above.1.as_ref()) {
+            (Some(l), Some(a)) => Some(l.op(a)),
+            (l, a) => l.or(a).cloned(),
+        }
+    };
+    (@value $mself:expr, $above:expr, $f:expr, off) => {{ let _ = $above; $f(&$mself.val) }};
+    (@value $mself:expr, $above:expr, $f:expr, [$lazy:ty]) => {
+        match $above.1 {
+            Some(ref e) => $f(&$mself.val.effect(e)),
+            None => $f(&$mself.val),
+        }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty | $r:tt | $l:tt | rev, $($tail:tt)*) => {
+        impl_pending_trait! { [$($g)*] $node, $val_type | on | $l | $($tail)* }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty | $r:tt | $l:tt | lazy($lazy:ty), $($tail:tt)*) => {
+        impl_pending_trait! { [$($g)*] $node, $val_type | $r | [$lazy] | $($tail)* }
     };
-    ($node:ident, $val_type:ty | $r:tt | $f:tt | fold, $($tail:tt)*) => {
-        impl_rev2_trait! { $node, $val_type | $r | on | $($tail)* }
+    ([$($g:tt)*] $node:ty, $val_type:ty | $r:tt | $l:tt | $head:ident ($($args:tt)*), $($tail:tt)*) => {
+        impl_pending_trait! { [$($g)*] $node, $val_type | $r | $l | $($tail)* }
     };
-    ($node:ident, $val_type:ty | $r:tt | $f:tt | $head:tt, $($tail:tt)*) => {
-        impl_rev2_trait! { $node, $val_type | $r | $f | $($tail)* }
+    ([$($g:tt)*] $node:ty, $val_type:ty | $r:tt | $l:tt | $head:tt, $($tail:tt)*) => {
+        impl_pending_trait! { [$($g)*] $node, $val_type | $r | $l | $($tail)* }
     };
 }
 
+#[macro_export]
+macro_rules! impl_effect_fold {
+    ($mself:expr, $e:expr, off) => {};
+    ($mself:expr, $e:expr, on) => { $mself.fold = $mself.fold.effect($e); };
+}
+
 #[macro_export]
 macro_rules! impl_size_trait {
-    ($node:ident, $val_type:ty, ) => {};
-    ($node:ident, $val_type:ty, size, $($tail:tt)*) => {
-        impl SizeNode for $node { fn size(&self) -> usize { self.size } }
+    // the leaves of an implicit tree are found by the position 0
+    (@leaf $leaf:ident, ) => {};
+    (@leaf $leaf:ident, size, $($tail:tt)*) => {
+        impl KeySearch<Position> for $leaf {
+            fn key_search(&self, key: Position) -> Option<(usize, Position)> {
+                match key {
+                    Position(0) => None,
+                    _ => Some((1, key)),
+                }
+            }
+        }
     };
-    ($node:ident, $val_type:ty, $head:tt, $($elem:tt)*) => {
-        impl_size_trait! { $node, $val_type, $($elem)* }
+    (@leaf $leaf:ident, $head:ident ($($args:tt)*), $($elem:tt)*) => {
+        impl_size_trait! { @leaf $leaf, $($elem)* }
+    };
+    (@leaf $leaf:ident, $head:tt, $($elem:tt)*) => {
+        impl_size_trait! { @leaf $leaf, $($elem)* }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty, ) => {};
+    ([$($g:tt)*] $node:ty, $val_type:ty, size, $($tail:tt)*) => {
+        impl<$($g)*> SizeNode for $node { fn size(&self) -> usize { self.size } }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty, $head:ident ($($args:tt)*), $($elem:tt)*) => {
+        impl_size_trait! { [$($g)*] $node, $val_type, $($elem)* }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty, $head:tt, $($elem:tt)*) => {
+        impl_size_trait! { [$($g)*] $node, $val_type, $($elem)* }
     };
 }
 
 #[macro_export]
 macro_rules! impl_height_trait {
-    ($node:ident, $val_type:ty, ) => {};
-    ($node:ident, $val_type:ty, height, $($tail:tt)*) => {
-        impl HeightNode for $node { fn height(&self) -> isize { self.height } }
+    ([$($g:tt)*] $node:ty, $val_type:ty, ) => {};
+    ([$($g:tt)*] $node:ty, $val_type:ty, height, $($tail:tt)*) => {
+        impl<$($g)*> HeightNode for $node { fn height(&self) -> isize { self.height } }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty, $head:ident ($($args:tt)*), $($elem:tt)*) => {
+        impl_height_trait! { [$($g)*] $node, $val_type, $($elem)* }
     };
-    ($node:ident, $val_type:ty, $head:tt, $($elem:tt)*) => {
-        impl_height_trait! { $node, $val_type, $($elem)* }
+    ([$($g:tt)*] $node:ty, $val_type:ty, $head:tt, $($elem:tt)*) => {
+        impl_height_trait! { [$($g)*] $node, $val_type, $($elem)* }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_priority_trait {
+    ([$($g:tt)*] $node:ty, $val_type:ty, ) => {};
+    ([$($g:tt)*] $node:ty, $val_type:ty, priority, $($tail:tt)*) => {
+        impl<$($g)*> PriorityNode for $node {
+            fn priority(&self) -> u64 { self.priority }
+            fn set_priority(&mut self, priority: u64) { self.priority = priority; }
+        }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty, $head:ident ($($args:tt)*), $($elem:tt)*) => {
+        impl_priority_trait! { [$($g)*] $node, $val_type, $($elem)* }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty, $head:tt, $($elem:tt)*) => {
+        impl_priority_trait! { [$($g)*] $node, $val_type, $($elem)* }
     };
 }
 
 #[macro_export]
 macro_rules! impl_fold_trait {
-    ($node:ident, $val_type:ty, ) => {};
-    ($node:ident, $val_type:ty, fold, $($tail:tt)*) => {
-        impl FoldNode for $node { fn fold(&self) -> $val_type { self.fold.clone() } }
+    ([$($g:tt)*] $node:ty, $val_type:ty, ) => {};
+    ([$($g:tt)*] $node:ty, $val_type:ty, fold, $($tail:tt)*) => {
+        impl<$($g)*> FoldNode for $node { fn fold(&self) -> $val_type { self.fold.clone() } }
+    };
+    ([$($g:tt)*] $node:ty, $val_type:ty, $head:ident ($($args:tt)*), $($elem:tt)*) => {
+        impl_fold_trait! { [$($g)*] $node, $val_type, $($elem)* }
     };
-    ($node:ident, $val_type:ty, $head:tt, $($elem:tt)*) => {
-        impl_fold_trait! { $node, $val_type, $($elem)* }
+    ([$($g:tt)*] $node:ty, $val_type:ty, $head:tt, $($elem:tt)*) => {
+        impl_fold_trait! { [$($g)*] $node, $val_type, $($elem)* }
     };
 }
 
 #[macro_export]
 macro_rules! impl_key_search {
-    ($node:ident, $key_type:ty) => {
-        impl<'a> KeySearch<&'a $key_type> for $node {
+    ([$($g:tt)*] $node:ty, $key_type:ty) => {
+        impl<'a, $($g)*> KeySearch<&'a $key_type> for $node {
             fn key_search(&self, key: &'a $key_type) -> Option<(usize, &'a $key_type)> {
                 match self.key.cmp(key) {
                     std::cmp::Ordering::Greater => Some((0, key)),
@@ -240,28 +418,150 @@ macro_rules! impl_key_search {
     }
 }
 
+#[macro_export]
+macro_rules! build_implicit_node_struct {
+    ($node:ident, $($elem:ident: $t:ty,)*) => {
+        struct $node {
+            parent: Parent<$node>,
+            child: [Child<$node>; 2],
+            $($elem: $t),*
+        }
+
+        impl Vertex for $node {
+            type N = $node;
+            fn parent(&self) -> &Parent<Self::N> { &self.parent }
+            fn replace_parent(&mut self, node: Parent<Self::N>) -> Parent<Self::N> { std::mem::replace(&mut self.parent, node) }
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! build_implicit_leaf_struct {
+    ($node:ident, $leaf:ident, $val_type:ty) => {
+        struct $leaf {
+            parent: Parent<$node>,
+            val: $val_type,
+        }
+        impl $leaf {
+            pub fn new(val: $val_type) -> Self {
+                $leaf {
+                    parent: None,
+                    val,
+                }
+            }
+        }
+        impl Vertex for $leaf {
+            type N = $node;
+            fn parent(&self) -> &Parent<Self::N> { &self.parent }
+            fn replace_parent(&mut self, node: Parent<Self::N>) -> Parent<Self::N> { std::mem::replace(&mut self.parent, node) }
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! impl_implicit_new {
+    ($node:ident, $l:ident, $r:ident, $($e:ident : $v:expr,)*) => {
+        $node {
+            parent: None,
+            child: [$l, $r],
+            $($e: $v),*
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! impl_implicit_node_trait {
+    ($node:ident, $leaf:ident, $val_type:ty, $($elem:tt)* ) => {
+        impl Node for $node {
+            type L = $leaf;
+            fn new(l: Child<Self>, r: Child<Self>) -> Self {
+                let mut n = node_inits! { impl_implicit_new { $node, l, r, } $val_type | | $($elem)* };
+                n.fix();
+                n
+            }
+            fn push(&mut self) {
+                impl_push! { implicit, self, $val_type, $($elem)* }
+            }
+            fn fix(&mut self) {
+                impl_fix! { implicit, self, $val_type, $($elem)* }
+            }
+            fn child(&self, dir: usize) -> &Child<Self> { &self.child[dir] }
+            fn child_mut(&mut self, dir: usize) -> &mut Child<Self> { &mut self.child[dir] }
+            fn replace(&mut self, dir: usize, node: Child<Self>) -> Child<Self> {
+                std::mem::replace(&mut self.child[dir], node)
+            }
+        }
+    } 
+}
 
+#[macro_export]
+macro_rules! impl_implicit_leaf_trait {
+    ($node:ident, $leaf:ident, $val_type:ty, $($elem:tt)* ) => {
+        impl Leaf for $leaf {
+            type Value = $val_type;
+            fn value(&self) -> &Self::Value { &self.val }
+            fn value_mut(&mut self) -> &mut Self::Value { &mut self.val }
+        }
+    } 
+}
 
+/// Defines a binary tree node usable by any tree built on `node_traits`.
+///
+/// Elements: `size`, `height`, `priority`, `fold`, `rev`, `lazy(E)`,
+/// `field(name: T = init)` for plain per-node data, and
+/// `aug(name: T = init, f)` for augmented data recomputed in `fix` as `f(&node)`.
+/// Elements are fixed in the order they are listed.
+///
+/// The node may be generic, as in `def_node! { pub MapNode<K: Ord, V>, key: K, V; size, height, }`.
+/// The bounds of the type parameters are lists of trait names.
+///
+/// `def_node! { implicit Node, Leaf, T; .. }` defines the internal node and the leaf of an
+/// `ImplicitAVLTree` over `implicit_avl_tree::node_traits` instead. Those cannot take `lazy(..)`.
 #[macro_export]
 macro_rules! def_node {
-    ($node:ident, $val_type:ty; $($elem:tt)* ) => {
-        define_node! { $node, $val_type | | $($elem)* }
-        impl_node_elem! { $node, $val_type | off | | $($elem)* }
-        impl_node_trait! { $node, $val_type, $($elem)* }
-        impl_rev2_trait! { $node, $val_type | off | off | $($elem)* }
-        impl_size_trait! { $node, $val_type, $($elem)* }
-        impl_height_trait! { $node, $val_type, $($elem)* }
-        impl_fold_trait! { $node, $val_type, $($elem)* }
-    };
-    ($node:ident, key: $key_type:ty, $val_type:ty; $($elem:tt)* ) => {
-        define_node! { $node, $val_type | key: $key_type, | $($elem)* }
-        impl_node_elem! { $node, $val_type | $key_type | | $($elem)* }
-        impl_node_trait! { $node, $val_type, $($elem)* }
-        impl_rev2_trait! { $node, $val_type | off | off | $($elem)* }
-        impl_size_trait! { $node, $val_type, $($elem)* }
-        impl_height_trait! { $node, $val_type, $($elem)* }
-        impl_fold_trait! { $node, $val_type, $($elem)* }
-        impl_key_search! { $node, $key_type }
+    (implicit $node:ident, $leaf:ident, $val_type:ty; $($elem:tt)* ) => {
+        node_fields! { build_implicit_node_struct { $node, } $val_type | | $($elem)* }
+        build_implicit_leaf_struct! { $node, $leaf, $val_type }
+        impl_implicit_node_trait! { $node, $leaf, $val_type, $($elem)* }
+        impl_implicit_leaf_trait! { $node, $leaf, $val_type, $($elem)* }
+        impl_rev_trait! { [] $node, $val_type | off | off | $($elem)* }
+        impl_size_trait! { [] $node, $val_type, $($elem)* }
+        impl_size_trait! { @leaf $leaf, $($elem)* }
+        impl_height_trait! { [] $node, $val_type, $($elem)* }
+        impl_fold_trait! { [] $node, $val_type, $($elem)* }
+    };
+    (@traits [$($g:tt)*] $node:ty, $val_type:ty; $($elem:tt)* ) => {
+        impl_node_trait! { [$($g)*] $node, $val_type, $($elem)* }
+        impl_rev_trait! { [$($g)*] $node, $val_type | off | off | $($elem)* }
+        impl_size_trait! { [$($g)*] $node, $val_type, $($elem)* }
+        impl_height_trait! { [$($g)*] $node, $val_type, $($elem)* }
+        impl_priority_trait! { [$($g)*] $node, $val_type, $($elem)* }
+        impl_fold_trait! { [$($g)*] $node, $val_type, $($elem)* }
+        impl_effect_trait! { [$($g)*] $node, $val_type | off | off | $($elem)* }
+        impl_pending_trait! { [$($g)*] $node, $val_type | off | off | $($elem)* }
+    };
+    (@keyed $vis:vis $name:ident [$($g:tt)*] $node:ty, $key_type:ty, $val_type:ty; $($elem:tt)* ) => {
+        node_fields! { build_node_struct { $vis $name [$($g)*], $val_type, key: $key_type, | } $val_type | | $($elem)* }
+        node_inits! { impl_node_key_new { $vis [$($g)*] $node, $val_type, $key_type | } $val_type | | $($elem)* }
+        def_node! { @traits [$($g)*] $node, $val_type; $($elem)* }
+        impl_key_search! { [$($g)*] $node, $key_type }
+    };
+    (@plain $vis:vis $name:ident [$($g:tt)*] $node:ty, $val_type:ty; $($elem:tt)* ) => {
+        node_fields! { build_node_struct { $vis $name [$($g)*], $val_type, | } $val_type | | $($elem)* }
+        node_inits! { impl_node_new { $vis [$($g)*] $node, $val_type | } $val_type | | $($elem)* }
+        def_node! { @traits [$($g)*] $node, $val_type; $($elem)* }
+    };
+    ($vis:vis $node:ident <$($p:ident $(: $b0:ident $(+ $b:ident)*)?),* $(,)?>, key: $key_type:ty, $val_type:ty; $($elem:tt)* ) => {
+        def_node! { @keyed $vis $node [$($p $(: $b0 $(+ $b)*)?),*] $node<$($p),*>, $key_type, $val_type; $($elem)* }
+    };
+    ($vis:vis $node:ident <$($p:ident $(: $b0:ident $(+ $b:ident)*)?),* $(,)?>, $val_type:ty; $($elem:tt)* ) => {
+        def_node! { @plain $vis $node [$($p $(: $b0 $(+ $b)*)?),*] $node<$($p),*>, $val_type; $($elem)* }
+    };
+    ($vis:vis $node:ident, key: $key_type:ty, $val_type:ty; $($elem:tt)* ) => {
+        def_node! { @keyed $vis $node [] $node, $key_type, $val_type; $($elem)* }
+    };
+    ($vis:vis $node:ident, $val_type:ty; $($elem:tt)* ) => {
+        def_node! { @plain $vis $node [] $node, $val_type; $($elem)* }
     };
 }
 
@@ -272,7 +572,10 @@ mod node_macro_test {
     struct M(usize);
     def_node! { NodeTest, M; size, rev, }
     def_node! { NodeTest2, key: usize, M; size, rev, }
-    
+    def_node! { NodeTest3, M; size, field(pri: u64 = 7), aug(sum: usize = 0, |n: &NodeTest3| {
+        n.val.0 + n.child[0].as_ref().map_or(0, |c| c.sum) + n.child[1].as_ref().map_or(0, |c| c.sum)
+    }), }
+
     #[test]
     fn node_macro_test() {
         let n = NodeTest { val: M(91), child: [Link::None, Link::None], size: 10, rev: false };
@@ -281,9 +584,74 @@ mod node_macro_test {
         let n = NodeTest::new(M(15));
         assert_eq!(n.val.0, 15);
         assert_eq!(n.size, 1);
-        assert_eq!(n.rev, false);
+        assert!(!n.rev);
 
         let n2 = NodeTest2::new(2, M(1333));
         assert_eq!(n2.key_search(&3).unwrap().0, 1);
     }
+
+    def_node! { GenericNode<T: Clone + Default>, T; size, rev, }
+    def_node! { GenericKeyNode<K: Ord, V>, key: K, V; size, }
+
+    #[test]
+    fn node_macro_generic_test() {
+        let mut n = GenericNode::new(vec![1, 2]);
+        let mut c = GenericNode::from_value(vec![3]);
+        c.fix();
+        n.replace(1, Link::Some(Box::new(c)));
+        n.fix();
+        assert_eq!(n.size(), 2);
+        assert_eq!(n.into_value(), vec![1, 2]);
+        let k = GenericKeyNode::new("b", 1.5);
+        assert_eq!(k.key_search(&"a").unwrap().0, 0);
+        assert!(k.key_search(&"b").is_none());
+        assert_eq!(*k.value(), 1.5);
+    }
+
+    #[test]
+    fn node_macro_aug_test() {
+        let mut n = NodeTest3::new(M(5));
+        assert_eq!(n.pri, 7);
+        assert_eq!(n.sum, 0);
+        n.fix();
+        assert_eq!(n.sum, 5);
+        let mut l = NodeTest3::new(M(3));
+        l.fix();
+        n.replace(0, Link::Some(Box::new(l)));
+        let mut r = NodeTest3::from_value(M(100));
+        r.fix();
+        n.replace(1, Link::Some(Box::new(r)));
+        n.fix();
+        assert_eq!(n.size(), 3);
+        assert_eq!(n.sum, 108);
+    }
+}
+
+#[cfg(test)]
+mod implicit_node_macro_test {
+    use crate::data_structures::implicit_avl_tree::node_traits::*;
+    use crate::algebra::*;
+
+    #[derive(Clone)]
+    struct Am(usize);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+
+    def_node! { implicit NodeTest, LeafTest, Am; size, height, fold, aug(weight: usize = 0, |n: &NodeTest| n.size * 10), }
+
+    #[test]
+    fn node_macro_test() {
+        let l = LeafTest::new(Am(10));
+        let r = LeafTest::new(Am(100));
+        let n = NodeTest::new(Child::Leaf(std::rc::Rc::new(std::cell::RefCell::new(l))), Child::Leaf(std::rc::Rc::new(std::cell::RefCell::new(r))));
+        assert_eq!(n.size(), 2);
+        assert_eq!(n.weight, 20);
+    }
 }
@@ -0,0 +1,394 @@
+use crate::data_structures::node_traits::*;
+
+use std::ops::{ Bound, RangeBounds };
+
+pub fn bounds<R: RangeBounds<usize>>(ran: R, len: usize) -> (usize, usize) {
+    let l = match ran.start_bound() {
+        Bound::Included(&l) => l,
+        Bound::Excluded(&l) => l + 1,
+        Bound::Unbounded => 0,
+    };
+    let r = match ran.end_bound() {
+        Bound::Included(&r) => r + 1,
+        Bound::Excluded(&r) => r,
+        Bound::Unbounded => len,
+    };
+    assert!(l <= r && r <= len);
+    (l, r)
+}
+
+pub fn at<K, N: Node + KeySearch<K>>(mut x: &mut Box<N>, mut pos: K) -> Option<&N::Value> {
+    x.push();
+    while let Some((dir, np)) = x.key_search(pos) {
+        pos = np;
+        x = x.child_mut(dir).as_mut()?;
+        x.push();
+    }
+    Some(x.value())
+}
+
+// balanced tree of the next `n` values of `iter`
+pub fn build<N: ValueNode, I: Iterator<Item = N::Value>>(iter: &mut I, n: usize) -> Link<N> {
+    if n == 0 { return Link::None }
+    let l = build(iter, n >> 1);
+    let mut x = Box::new(N::from_value(iter.next().unwrap()));
+    let r = build(iter, n - (n >> 1) - 1);
+    x.replace(0, l);
+    x.replace(1, r);
+    x.fix();
+    Link::Some(x)
+}
+
+pub fn into_vec<N: ValueNode>(mut x: Link<N>, vec: &mut Vec<N::Value>) {
+    let mut stack = Vec::new();
+    loop {
+        while let Link::Some(mut node) = x {
+            node.push();
+            x = node.replace(0, Link::None);
+            stack.push(node);
+        }
+        match stack.pop() {
+            Some(mut node) => {
+                x = node.replace(1, Link::None);
+                vec.push(node.into_value());
+            }
+            None => break,
+        }
+    }
+}
+
+pub fn to_vec<N: Node + SizeNode>(x: &mut Link<N>, vec: &mut Vec<N::Value>) where N::Value: Clone {
+    let n = x.size();
+    push_range(x, 0, n);
+    vec.extend(Iter::new(x.as_ref().map(|r| &**r), 0, n).cloned());
+}
+
+// pushes every node whose subtree meets `l..r`. the subtrees being visited are detached
+// from their parents, which get them back once they are done
+pub fn push_range<N: Node + SizeNode>(x: &mut Link<N>, l: usize, r: usize) {
+    let root = match x.take() {
+        Link::Some(root) => root,
+        link => { *x = link; return }
+    };
+    // (node, range in it, size of its left child, children visited so far)
+    let mut stack = vec![(root, l, r, 0, 0)];
+    while let Some((node, l, r, ls, visited)) = stack.last_mut() {
+        let (l, r) = (*l, *r);
+        match *visited {
+            0 => {
+                node.push();
+                *ls = node.child(0).size();
+                *visited = 1;
+                if l < *ls {
+                    if let Link::Some(c) = node.replace(0, Link::None) {
+                        let rr = std::cmp::min(r, *ls);
+                        stack.push((c, l, rr, 0, 0));
+                    }
+                }
+            }
+            1 => {
+                let ls = *ls;
+                *visited = 2;
+                if ls + 1 < r {
+                    if let Link::Some(c) = node.replace(1, Link::None) {
+                        stack.push((c, std::cmp::max(l, ls + 1) - ls - 1, r - ls - 1, 0, 0));
+                    }
+                }
+            }
+            _ => {
+                let (node, ..) = stack.pop().unwrap();
+                match stack.last_mut() {
+                    Some((p, _, _, _, visited)) => { p.replace(*visited - 1, Link::Some(node)); }
+                    None => *x = Link::Some(node),
+                }
+            }
+        }
+    }
+}
+
+fn child<N: Node>(x: &N, dir: usize) -> Option<&N> {
+    x.child(dir).as_ref().map(|ch| &**ch)
+}
+
+// all the nodes in the range must have been pushed
+pub struct Iter<'a, N: Node> {
+    front: Vec<&'a N>,
+    back: Vec<&'a N>,
+    len: usize,
+}
+
+impl<'a, N: Node + SizeNode> Iter<'a, N> {
+    pub fn new(root: Option<&'a N>, l: usize, r: usize) -> Self {
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        if l < r {
+            let (mut x, mut pos) = (root, l);
+            while let Some(node) = x {
+                let ls = node.child(0).size();
+                if pos <= ls { front.push(node); }
+                if pos == ls { break }
+                x = if pos < ls { child(node, 0) } else { pos -= ls + 1; child(node, 1) };
+            }
+            let (mut x, mut pos) = (root, r - 1);
+            while let Some(node) = x {
+                let ls = node.child(0).size();
+                if ls <= pos { back.push(node); }
+                if pos == ls { break }
+                x = if pos < ls { child(node, 0) } else { pos -= ls + 1; child(node, 1) };
+            }
+        }
+        Iter { front, back, len: r - l }
+    }
+}
+
+impl<'a, N: Node> Iterator for Iter<'a, N> {
+    type Item = &'a N::Value;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 { return None }
+        self.len -= 1;
+        let node = self.front.pop().unwrap();
+        let mut x = child(node, 1);
+        while let Some(ch) = x {
+            self.front.push(ch);
+            x = child(ch, 0);
+        }
+        Some(node.value())
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.len, Some(self.len)) }
+}
+
+impl<'a, N: Node> DoubleEndedIterator for Iter<'a, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 { return None }
+        self.len -= 1;
+        let node = self.back.pop().unwrap();
+        let mut x = child(node, 0);
+        while let Some(ch) = x {
+            self.back.push(ch);
+            x = child(ch, 1);
+        }
+        Some(node.value())
+    }
+}
+
+impl<'a, N: Node> ExactSizeIterator for Iter<'a, N> {}
+
+// formats the values in order as if every pending push were done, without pushing
+pub fn fmt_values<N: PendingNode>(root: Option<&N>, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+where N::Value: std::fmt::Debug {
+    let mut list = f.debug_list();
+    let mut stack = Vec::new();
+    let mut cur = root.map(|x| (x, N::Pending::default()));
+    loop {
+        while let Some((x, above)) = cur {
+            let (below, swap) = x.child_pending(&above);
+            cur = child(x, swap as usize).map(|c| (c, below.clone()));
+            stack.push((x, above, below, swap));
+        }
+        match stack.pop() {
+            Some((x, above, below, swap)) => {
+                x.with_pushed_value(&above, |v| { list.entry(v); });
+                cur = child(x, !swap as usize).map(|c| (c, below));
+            }
+            None => break,
+        }
+    }
+    list.finish()
+}
+
+// the sequence API shared by the trees over `node_traits`.
+// `$tree` must have a `root: Link<N>` field and `empty`, `new`, `merge`, `split`, `root`,
+// `into_root` and `from_vec` methods, and `node_traits` and `algebra` must be in scope.
+#[macro_export]
+macro_rules! impl_sequence {
+    ($tree:ident, $node:ident) => {
+        impl<N: $node + SizeNode> $tree<N> {
+            pub fn size(&self) -> usize {
+                self.root.size()
+            }
+            // applies `f` to the subtree of the range
+            fn with_range<R: std::ops::RangeBounds<usize>, T, F: FnOnce(&mut Self) -> T>(&mut self, ran: R, f: F) -> T {
+                let (l, r) = $crate::data_structures::node_seq::bounds(ran, self.size());
+                let tree = std::mem::replace(self, Self::empty());
+                let (a, bc) = tree.split(Position(l));
+                let (mut b, c) = bc.split(Position(r - l));
+                let res = f(&mut b);
+                *self = a.merge(b).merge(c);
+                res
+            }
+            pub fn insert(&mut self, pos: usize, val: N::Value) where N: ValueNode {
+                assert!(pos <= self.size());
+                let tree = std::mem::replace(self, Self::empty());
+                let (l, r) = tree.split(Position(pos));
+                *self = l.merge(Self::new(N::from_value(val))).merge(r);
+            }
+            pub fn erase(&mut self, pos: usize) -> N::Value where N: ValueNode {
+                assert!(pos < self.size());
+                let tree = std::mem::replace(self, Self::empty());
+                let (l, mr) = tree.split(Position(pos));
+                let (m, r) = mr.split(Position(1));
+                *self = l.merge(r);
+                m.into_root().unwrap().into_value()
+            }
+            pub fn into_vec(mut self) -> Vec<N::Value> where N: ValueNode {
+                let mut vec = Vec::with_capacity(self.size());
+                $crate::data_structures::node_seq::into_vec(self.root.take(), &mut vec);
+                vec
+            }
+            pub fn to_vec(&mut self) -> Vec<N::Value> where N::Value: Clone {
+                let mut vec = Vec::with_capacity(self.size());
+                $crate::data_structures::node_seq::to_vec(&mut self.root, &mut vec);
+                vec
+            }
+            pub fn range<R: std::ops::RangeBounds<usize>>(&mut self, ran: R) -> $crate::data_structures::node_seq::Iter<'_, N> {
+                let (l, r) = $crate::data_structures::node_seq::bounds(ran, self.size());
+                $crate::data_structures::node_seq::push_range(&mut self.root, l, r);
+                $crate::data_structures::node_seq::Iter::new(self.root(), l, r)
+            }
+            pub fn iter(&mut self) -> $crate::data_structures::node_seq::Iter<'_, N> {
+                self.range(..)
+            }
+            pub fn update<R: std::ops::RangeBounds<usize>, E>(&mut self, ran: R, e: E) where N: EffectNode<E> {
+                self.with_range(ran, |t| {
+                    if let Link::Some(ref mut r) = t.root { r.effect(&e); }
+                })
+            }
+        }
+
+        impl<'a, N: $node + SizeNode> IntoIterator for &'a mut $tree<N> {
+            type Item = &'a N::Value;
+            type IntoIter = $crate::data_structures::node_seq::Iter<'a, N>;
+            fn into_iter(self) -> Self::IntoIter { self.iter() }
+        }
+
+        impl<N: $node + SizeNode + ValueNode> IntoIterator for $tree<N> {
+            type Item = N::Value;
+            type IntoIter = std::vec::IntoIter<N::Value>;
+            fn into_iter(self) -> Self::IntoIter { self.into_vec().into_iter() }
+        }
+
+        impl<N: $node + SizeNode + ValueNode> std::iter::FromIterator<N::Value> for $tree<N> {
+            fn from_iter<I: IntoIterator<Item = N::Value>>(iter: I) -> Self {
+                Self::from_vec(iter.into_iter().collect())
+            }
+        }
+
+        impl<N: $node + PendingNode> std::fmt::Debug for $tree<N> where N::Value: std::fmt::Debug {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                $crate::data_structures::node_seq::fmt_values(self.root(), f)
+            }
+        }
+
+        impl<N: $node + ReversibleNode> $tree<N> {
+            pub fn reverse(&mut self) {
+                if let Link::Some(ref mut r) = self.root {
+                    r.reverse()
+                }
+            }
+            pub fn reverse_range<R: std::ops::RangeBounds<usize>>(&mut self, ran: R) where N: SizeNode {
+                self.with_range(ran, |t| t.reverse())
+            }
+        }
+
+        impl<N: $node + FoldNode> $tree<N> where <N as Node>::Value: Monoid {
+            pub fn all_fold(&self) -> N::Value {
+                match self.root {
+                    Link::Some(ref node) => node.fold(),
+                    _ => <N as Node>::Value::identity(),
+                }
+            }
+            pub fn fold<R: std::ops::RangeBounds<usize>>(&mut self, ran: R) -> N::Value where N: SizeNode {
+                self.with_range(ran, |t| t.all_fold())
+            }
+        }
+    };
+}
+
+// random operations on `$tree` checked against a `Vec`, and a sequential-access workload.
+// `$attr` are the node attributes `$tree` needs besides `size`, `fold`, `rev` and `lazy`
+#[cfg(test)]
+macro_rules! sequence_test {
+    ($tree:ident; $($attr:ident),*) => {
+        use crate::data_structures::node_traits::*;
+        use crate::algebra::*;
+        use crate::random::{ Random, Xorshift128 };
+
+        monoid! { #[derive(Debug)] Sum, (i64, i64), (0, 0), |a, b| (a.0 + b.0, a.1 + b.1) }
+        impl Reverse for Sum {
+            fn reverse(&self) -> Self { self.clone() }
+        }
+        monoid! { Add, i64, 0, |a, b| a + b }
+        action! { Add => Sum, |x, f| (x.0 + f * x.1, x.1) }
+
+        def_node! { NodeTest, Sum; size, $($attr,)* fold, rev, lazy(Add), }
+
+        #[test]
+        fn random_test() {
+            let mut rng = Xorshift128::new(42);
+            let mut vec = (0..30).map(|i| i as i64).collect::<Vec<_>>();
+            let mut arr = $tree::<NodeTest>::from_vec(vec.iter().map(|&x| Sum((x, 1))).collect());
+            for _ in 0..2000 {
+                let n = vec.len();
+                let l = (rng.rand_u64() % (n as u64 + 1)) as usize;
+                let r = l + (rng.rand_u64() % ((n - l) as u64 + 1)) as usize;
+                let x = (rng.rand_u64() % 100) as i64;
+                match rng.rand_u64() % 7 {
+                    0 => {
+                        arr.insert(l, Sum((x, 1)));
+                        vec.insert(l, x);
+                    }
+                    1 if l < n => assert_eq!(arr.erase(l).0.0, vec.remove(l)),
+                    2 => {
+                        arr.update(l..r, Add(x));
+                        for v in vec[l..r].iter_mut() { *v += x; }
+                    }
+                    3 => {
+                        arr.reverse_range(l..r);
+                        vec[l..r].reverse();
+                    }
+                    4 => assert_eq!(arr.fold(l..r).0.0, vec[l..r].iter().sum::<i64>()),
+                    5 if l < n => {
+                        assert_eq!(arr.at(Position(l)).unwrap().0.0, vec[l]);
+                        arr.set(Position(l), Sum((x, 1)));
+                        vec[l] = x;
+                    }
+                    _ => assert_eq!(arr.fold(l..).0.0, vec[l..].iter().sum::<i64>()),
+                }
+                assert_eq!(arr.size(), vec.len());
+            }
+            assert_eq!(format!("{:?}", arr), format!("{:?}", arr.to_vec()));
+            assert_eq!(arr.iter().map(|s| s.0.0).collect::<Vec<_>>(), vec);
+            assert_eq!(arr.to_vec().iter().map(|s| s.0.0).collect::<Vec<_>>(), vec);
+            assert_eq!(arr.all_fold().0.1, vec.len() as i64);
+            assert_eq!(arr.into_vec().into_iter().map(|s| s.0.0).collect::<Vec<_>>(), vec);
+        }
+
+        #[test]
+        fn empty_test() {
+            let mut arr = $tree::<NodeTest>::empty();
+            assert!(arr.at(Position(0)).is_none());
+            arr.insert(0, Sum((5, 1)));
+            assert_eq!(arr.at(Position(0)).unwrap().0.0, 5);
+            assert!(arr.at(Position(1)).is_none());
+            arr.erase(0);
+            assert!(arr.at(Position(0)).is_none());
+        }
+
+        #[test]
+        fn sequential_access_test() {
+            let n = 1_000_000;
+            let accessed = || {
+                let mut arr = $tree::<NodeTest>::from_vec((0..n).map(|x| Sum((x, 1))).collect());
+                for i in 0..n {
+                    assert_eq!(arr.at(Position(i as usize)).unwrap().0.0, i);
+                }
+                arr
+            };
+            let mut arr = accessed();
+            assert!(arr.to_vec().iter().map(|s| s.0.0).eq(0..n));
+            assert!(arr.iter().map(|s| s.0.0).eq(0..n));
+            assert!(arr.into_vec().into_iter().map(|s| s.0.0).eq(0..n));
+            drop(accessed());
+        }
+    };
+}
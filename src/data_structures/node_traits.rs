@@ -46,12 +46,33 @@ pub trait KeySearch<K> {
     fn key_search(&self, key: K) -> Option<(usize, K)>;
 }
 
+pub trait ValueNode: Node {
+    fn from_value(val: Self::Value) -> Self;
+    fn into_value(self) -> Self::Value;
+}
+
 pub trait ReversibleNode { fn reverse(&mut self); }
 
+pub trait EffectNode<E> { fn effect(&mut self, e: &E); }
+
 pub trait SizeNode { fn size(&self) -> usize; }
 
 pub trait HeightNode { fn height(&self) -> isize; }
 
+pub trait PriorityNode {
+    fn priority(&self) -> u64;
+    fn set_priority(&mut self, priority: u64);
+}
+
+// the pushes pending above a node, so that read-only traversals can see the pushed values
+pub trait PendingNode: Node {
+    type Pending: Clone + Default;
+    // what is pending above the children given what is pending above `self`,
+    // and whether the children get swapped by the push
+    fn child_pending(&self, above: &Self::Pending) -> (Self::Pending, bool);
+    fn with_pushed_value<R, F: FnOnce(&Self::Value) -> R>(&self, above: &Self::Pending, f: F) -> R;
+}
+
 pub trait FoldNode where Self: Node, <Self as Node>::Value: Monoid {
     fn fold(&self) -> <Self as Node>::Value;
 }
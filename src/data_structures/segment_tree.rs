@@ -1,5 +1,11 @@
 pub mod segment_tree;
 pub mod lazy_segment_tree;
 pub mod dynamic_segment_tree;
+pub mod dynamic_lazy_segment_tree;
 pub mod persistent_segment_tree;
 pub mod persistent_lazy_segment_tree;
+pub mod versioned_segment_tree;
+pub mod segment_tree_beats;
+pub mod segment_tree_2d;
+pub mod dynamic_segment_tree_2d;
+pub mod range_tree;
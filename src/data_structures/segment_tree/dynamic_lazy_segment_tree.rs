@@ -0,0 +1,272 @@
+use crate::algebra::*;
+
+use std::ops::{ Bound, RangeBounds };
+
+const NIL: usize = usize::MAX;
+
+struct Node<T, E> {
+    val: T,
+    lazy: E,
+    flag: bool,
+    ch: [usize; 2],
+}
+
+pub struct DynamicLazySegmentTree<T: Monoid + Effect<E>, E: Monoid> {
+    node: Vec<Node<T, E>>,
+    root: usize,
+    lo: i64,
+    // positions are offsets from `lo`. a domain may be 2^64 wide, so they are u128
+    len: u128,
+    n: u128,
+    pw: Vec<T>,
+}
+
+impl<T: Monoid + Effect<E>, E: Monoid> DynamicLazySegmentTree<T, E> {
+    // the domain may be all of i64, as in `new(.., init)`
+    pub fn new<R: RangeBounds<i64>>(ran: R, init: T) -> Self {
+        let lo = match ran.start_bound() {
+            Bound::Included(&l) => l as i128,
+            Bound::Excluded(&l) => l as i128 + 1,
+            Bound::Unbounded => i64::MIN as i128,
+        };
+        let hi = match ran.end_bound() {
+            Bound::Included(&r) => r as i128 + 1,
+            Bound::Excluded(&r) => r as i128,
+            Bound::Unbounded => i64::MAX as i128 + 1,
+        };
+        assert!(lo < hi);
+        let len = (hi - lo) as u128;
+        let n = len.next_power_of_two();
+        let h = n.trailing_zeros() as usize;
+        let mut pw = vec![init; h + 1];
+        for d in (0..h).rev() { pw[d] = pw[d + 1].op(&pw[d + 1]); }
+        DynamicLazySegmentTree { node: Vec::new(), root: NIL, lo: lo as i64, len, n, pw }
+    }
+
+    pub fn clear(&mut self) {
+        self.node.clear();
+        self.root = NIL;
+    }
+
+    // `i` may be the end of the domain
+    fn offset(&self, i: i64) -> u128 {
+        assert!(self.lo <= i, "index out of the domain");
+        let o = (i as i128 - self.lo as i128) as u128;
+        assert!(o <= self.len, "index out of the domain");
+        o
+    }
+
+    fn offsets<R: RangeBounds<i64>>(&self, ran: R) -> (u128, u128) {
+        let a = match ran.start_bound() {
+            Bound::Included(&l) => self.offset(l),
+            Bound::Excluded(&l) => self.offset(l) + 1,
+            Bound::Unbounded => 0,
+        };
+        let b = match ran.end_bound() {
+            Bound::Included(&r) => self.offset(r) + 1,
+            Bound::Excluded(&r) => self.offset(r),
+            Bound::Unbounded => self.len,
+        };
+        assert!(a <= b && b <= self.len, "index out of the domain");
+        (a, b)
+    }
+
+    fn alloc(&mut self, depth: usize) -> usize {
+        self.node.push(Node { val: self.pw[depth].clone(), lazy: E::identity(), flag: false, ch: [NIL, NIL] });
+        self.node.len() - 1
+    }
+
+    fn root(&mut self) -> usize {
+        if self.root == NIL { self.root = self.alloc(0); }
+        self.root
+    }
+
+    fn child(&mut self, k: usize, dir: usize, depth: usize) -> usize {
+        if self.node[k].ch[dir] == NIL {
+            let c = self.alloc(depth + 1);
+            self.node[k].ch[dir] = c;
+        }
+        self.node[k].ch[dir]
+    }
+
+    fn apply(&mut self, k: usize, e: &E) {
+        self.node[k].val = self.node[k].val.effect(e);
+        self.node[k].lazy = self.node[k].lazy.op(e);
+        self.node[k].flag = true;
+    }
+
+    fn push(&mut self, k: usize, depth: usize) -> (usize, usize) {
+        let c0 = self.child(k, 0, depth);
+        let c1 = self.child(k, 1, depth);
+        if self.node[k].flag {
+            let e = std::mem::replace(&mut self.node[k].lazy, E::identity());
+            self.apply(c0, &e);
+            self.apply(c1, &e);
+            self.node[k].flag = false;
+        }
+        (c0, c1)
+    }
+
+    fn fix(&mut self, k: usize, c0: usize, c1: usize) {
+        self.node[k].val = self.node[c0].val.op(&self.node[c1].val);
+    }
+
+    fn update_rec(&mut self, k: usize, (a, b): (u128, u128), l: u128, r: u128, e: &E, depth: usize) {
+        if b <= l || r <= a { return }
+        if a <= l && r <= b {
+            self.apply(k, e);
+            return
+        }
+        let (c0, c1) = self.push(k, depth);
+        let m = l + ((r - l) >> 1);
+        self.update_rec(c0, (a, b), l, m, e, depth + 1);
+        self.update_rec(c1, (a, b), m, r, e, depth + 1);
+        self.fix(k, c0, c1);
+    }
+
+    fn set_rec(&mut self, k: usize, i: u128, x: T, l: u128, r: u128, depth: usize) {
+        if l + 1 == r {
+            self.node[k].val = x;
+            return
+        }
+        let (c0, c1) = self.push(k, depth);
+        let m = l + ((r - l) >> 1);
+        if i < m { self.set_rec(c0, i, x, l, m, depth + 1); }
+        else { self.set_rec(c1, i, x, m, r, depth + 1); }
+        self.fix(k, c0, c1);
+    }
+
+    fn fold_rec(&mut self, k: usize, a: u128, b: u128, l: u128, r: u128, depth: usize) -> T {
+        if b <= l || r <= a { return T::identity() }
+        if a <= l && r <= b { return self.node[k].val.clone() }
+        let (c0, c1) = self.push(k, depth);
+        let m = l + ((r - l) >> 1);
+        self.fold_rec(c0, a, b, l, m, depth + 1).op(&self.fold_rec(c1, a, b, m, r, depth + 1))
+    }
+
+    pub fn update<R: RangeBounds<i64>>(&mut self, ran: R, e: E) {
+        let (a, b) = self.offsets(ran);
+        let root = self.root();
+        self.update_rec(root, (a, b), 0, self.n, &e, 0);
+    }
+
+    pub fn set(&mut self, i: i64, x: T) {
+        let i = self.offset(i);
+        assert!(i < self.len, "index out of the domain");
+        let root = self.root();
+        self.set_rec(root, i, x, 0, self.n, 0);
+    }
+
+    pub fn get(&mut self, i: i64) -> T {
+        self.fold(i..=i)
+    }
+
+    pub fn fold<R: RangeBounds<i64>>(&mut self, ran: R) -> T {
+        let (a, b) = self.offsets(ran);
+        let root = self.root();
+        self.fold_rec(root, a, b, 0, self.n, 0)
+    }
+
+    pub fn all_fold(&mut self) -> T {
+        let root = self.root();
+        self.fold_rec(root, 0, self.len, 0, self.n, 0)
+    }
+}
+
+#[cfg(test)]
+mod dynamic_lazy_segment_tree_test {
+    use crate::data_structures::segment_tree::dynamic_lazy_segment_tree::DynamicLazySegmentTree;
+    use crate::random::{ Random, Xorshift128 };
+
+    monoid! { Sum, (i64, i64), (0, 0), |a, b| (a.0 + b.0, a.1 + b.1) }
+    monoid! { Add, i64, 0, |a, b| a + b }
+    action! { Add => Sum, |x, f| (x.0 + f * x.1, x.1) }
+
+    #[test]
+    fn small_random_test() {
+        let mut rng = Xorshift128::new(6);
+        let (lo, hi) = (-37i64, 41i64);
+        let mut seg = DynamicLazySegmentTree::new(lo..hi, Sum((0, 1)));
+        for _ in 0..3 {
+            seg.clear();
+            let mut arr = vec![0i64; (hi - lo) as usize];
+            for _ in 0..500 {
+                let l = lo + (rng.rand_u64() % (hi - lo) as u64) as i64;
+                let r = l + 1 + (rng.rand_u64() % (hi - l) as u64) as i64;
+                let x = (rng.rand_u64() % 100) as i64;
+                match rng.rand_u64() % 3 {
+                    0 => {
+                        seg.update(l..r, Add(x));
+                        for i in l..r { arr[(i - lo) as usize] += x; }
+                    }
+                    1 => {
+                        seg.set(l, Sum((x, 1)));
+                        arr[(l - lo) as usize] = x;
+                    }
+                    _ => {
+                        let expected = (l..r).map(|i| arr[(i - lo) as usize]).sum::<i64>();
+                        assert_eq!(seg.fold(l..r).0.0, expected);
+                    }
+                }
+            }
+            assert_eq!(seg.all_fold().0.0, arr.iter().sum::<i64>());
+            assert_eq!(seg.all_fold().0.1, hi - lo);
+        }
+    }
+
+    #[test]
+    fn large_domain_test() {
+        let mut rng = Xorshift128::new(7);
+        let lim = 1_000_000_000_000_000_000i64;
+        let mut seg = DynamicLazySegmentTree::new(-lim..lim + 1, Sum((0, 1)));
+        let mut ups = Vec::new();
+        for _ in 0..200 {
+            let l = (rng.rand_u64() % (2 * lim as u64)) as i64 - lim;
+            let r = std::cmp::min(l + (rng.rand_u64() % 1_000_000) as i64 + 1, lim);
+            let x = (rng.rand_u64() % 100) as i64;
+            seg.update(l..r, Add(x));
+            ups.push((l, r, x));
+            let ql = (rng.rand_u64() % (2 * lim as u64)) as i64 - lim;
+            let qr = std::cmp::min(ql + (rng.rand_u64() % (lim as u64)) as i64 + 1, lim);
+            let expected = ups.iter()
+                .map(|&(l, r, x)| x * std::cmp::max(0, std::cmp::min(r, qr) - std::cmp::max(l, ql)))
+                .sum::<i64>();
+            assert_eq!(seg.fold(ql..qr).0.0, expected);
+            assert_eq!(seg.get(l).0.0, ups.iter().filter(|u| u.0 <= l && l < u.1).map(|u| u.2).sum::<i64>());
+        }
+        assert_eq!(seg.fold(-lim..lim + 1).0.1, 2 * lim + 1);
+    }
+
+    monoid! { Max, i64, i64::MIN, |a, b| std::cmp::max(a, b) }
+    monoid! { AddMax, i64, 0, |a, b| a + b }
+    action! { AddMax => Max, |x, f| if x == i64::MIN { x } else { x + f } }
+
+    #[test]
+    fn full_domain_test() {
+        let mut seg = DynamicLazySegmentTree::new(.., Max(0));
+        seg.update(..0, AddMax(3));
+        seg.update(-5.., AddMax(4));
+        seg.set(i64::MAX - 1, Max(100));
+        assert_eq!(seg.get(i64::MIN).0, 3);
+        assert_eq!(seg.get(-1).0, 7);
+        assert_eq!(seg.get(0).0, 4);
+        assert_eq!(seg.get(i64::MAX).0, 4);
+        assert_eq!(seg.fold(..-5).0, 3);
+        assert_eq!(seg.fold(0..i64::MAX - 1).0, 4);
+        assert_eq!(seg.all_fold().0, 100);
+        seg.set(i64::MAX, Max(200));
+        assert_eq!(seg.fold(i64::MAX..=i64::MAX).0, 200);
+        assert_eq!(seg.fold(i64::MIN..=i64::MAX).0, 200);
+        let mut seg = DynamicLazySegmentTree::new(i64::MIN..=i64::MAX, Max(0));
+        seg.update(i64::MAX.., AddMax(5));
+        assert_eq!(seg.fold(1..).0, 5);
+        assert_eq!(seg.fold(..i64::MAX).0, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_domain_test() {
+        let mut seg = DynamicLazySegmentTree::new(-10..10, Max(0));
+        seg.fold(-11..0);
+    }
+}
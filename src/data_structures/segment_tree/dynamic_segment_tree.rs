@@ -105,11 +105,68 @@ impl<T: Monoid> Node<T> {
             &Node::None => T::identity(),
         }
     }
+    fn max_right<F>(&self, a: usize, l: usize, r: usize, acc: &mut T, pred: &F) -> Option<usize>
+    where F: Fn(&T) -> bool {
+        if r <= a { return None }
+        match *self {
+            Node::Section(ref sec) => {
+                if a <= l {
+                    let res = acc.op(&sec.fold());
+                    if pred(&res) {
+                        *acc = res;
+                        return None;
+                    }
+                }
+                let m = (l + r) >> 1;
+                sec.left.max_right(a, l, m, acc, pred)
+                    .or_else(|| sec.right.max_right(a, m, r, acc, pred))
+            }
+            Node::Leaf(ref leaf) => {
+                if leaf.i < a { return None }
+                let res = acc.op(&leaf.fold());
+                if pred(&res) {
+                    *acc = res;
+                    None
+                }
+                else { Some(leaf.i) }
+            }
+            Node::None => None,
+        }
+    }
+    fn min_left<F>(&self, b: usize, l: usize, r: usize, acc: &mut T, pred: &F) -> Option<usize>
+    where F: Fn(&T) -> bool {
+        if b <= l { return None }
+        match *self {
+            Node::Section(ref sec) => {
+                if r <= b {
+                    let res = sec.fold().op(acc);
+                    if pred(&res) {
+                        *acc = res;
+                        return None;
+                    }
+                }
+                let m = (l + r) >> 1;
+                sec.right.min_left(b, m, r, acc, pred)
+                    .or_else(|| sec.left.min_left(b, l, m, acc, pred))
+            }
+            Node::Leaf(ref leaf) => {
+                if b <= leaf.i { return None }
+                let res = leaf.fold().op(acc);
+                if pred(&res) {
+                    *acc = res;
+                    None
+                }
+                else { Some(leaf.i + 1) }
+            }
+            Node::None => None,
+        }
+    }
 }
 
 pub struct DynamicSegmentTree<T: Monoid> {
     root: Node<T>,
     n: usize,
+    sz: usize,
 }
 
 impl<T: Monoid> DynamicSegmentTree<T> {
@@ -119,6 +176,7 @@ impl<T: Monoid> DynamicSegmentTree<T> {
         DynamicSegmentTree {
             root: Node::None,
             n: n,
+            sz,
         }
     }
     pub fn set(&mut self, i: usize, x: T) {
@@ -128,4 +186,51 @@ impl<T: Monoid> DynamicSegmentTree<T> {
     pub fn fold(&self, ran: Range<usize>) -> T {
         self.root.range_fold(ran.start, ran.end, 0, self.n)
     }
+    pub fn max_right<F>(&self, l: usize, pred: F) -> usize where F: Fn(&T) -> bool {
+        assert!(pred(&T::identity()));
+        let mut acc = T::identity();
+        self.root.max_right(l, 0, self.n, &mut acc, &pred).unwrap_or(self.sz)
+    }
+    pub fn min_left<F>(&self, r: usize, pred: F) -> usize where F: Fn(&T) -> bool {
+        assert!(pred(&T::identity()));
+        let mut acc = T::identity();
+        self.root.min_left(r, 0, self.n, &mut acc, &pred).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod dynamic_segment_tree_test {
+    use crate::algebra::*;
+    use crate::data_structures::segment_tree::dynamic_segment_tree::DynamicSegmentTree;
+
+    #[derive(Clone)]
+    struct Am(usize);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+
+    #[test]
+    fn binary_search_test() {
+        let n = 1_000_000_000;
+        let mut seg = DynamicSegmentTree::new(n);
+        seg.set(10, Am(3));
+        seg.set(500, Am(1));
+        seg.set(123_456_789, Am(4));
+        seg.set(999_999_999, Am(1));
+        assert_eq!(seg.fold(0..n).0, 9);
+        assert_eq!(seg.max_right(0, |x| x.0 <= 2), 10);
+        assert_eq!(seg.max_right(0, |x| x.0 <= 3), 500);
+        assert_eq!(seg.max_right(11, |x| x.0 <= 4), 123_456_789);
+        assert_eq!(seg.max_right(11, |x| x.0 <= 9), n);
+        assert_eq!(seg.min_left(n, |x| x.0 == 0), 1_000_000_000);
+        assert_eq!(seg.min_left(n, |x| x.0 <= 5), 501);
+        assert_eq!(seg.min_left(500, |x| x.0 <= 3), 0);
+        assert_eq!(seg.min_left(501, |x| x.0 <= 3), 11);
+    }
 }
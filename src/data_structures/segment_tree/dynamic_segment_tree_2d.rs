@@ -0,0 +1,112 @@
+use crate::algebra::*;
+use crate::data_structures::segment_tree::dynamic_segment_tree::DynamicSegmentTree;
+
+use std::ops::Range;
+
+struct Node<T: Monoid> {
+    seg: DynamicSegmentTree<T>,
+    left: Option<Box<Node<T>>>,
+    right: Option<Box<Node<T>>>,
+}
+
+impl<T: Monoid> Node<T> {
+    fn new(w: usize) -> Self {
+        Node { seg: DynamicSegmentTree::new(w), left: None, right: None }
+    }
+    fn fold_at(node: &Option<Box<Node<T>>>, j: usize) -> T {
+        match *node {
+            Some(ref n) => n.seg.fold(j..j + 1),
+            None => T::identity(),
+        }
+    }
+    fn set(&mut self, i: usize, j: usize, x: T, l: usize, r: usize, w: usize) {
+        if l + 1 == r {
+            self.seg.set(j, x);
+        }
+        else {
+            let m = (l + r) >> 1;
+            if i < m {
+                self.left.get_or_insert_with(|| Box::new(Node::new(w))).set(i, j, x, l, m, w);
+            }
+            else {
+                self.right.get_or_insert_with(|| Box::new(Node::new(w))).set(i, j, x, m, r, w);
+            }
+            let val = Self::fold_at(&self.left, j).op(&Self::fold_at(&self.right, j));
+            self.seg.set(j, val);
+        }
+    }
+    fn fold(&self, a: usize, b: usize, cols: &Range<usize>, l: usize, r: usize) -> T {
+        if b <= l || r <= a { T::identity() }
+        else if a <= l && r <= b { self.seg.fold(cols.clone()) }
+        else {
+            let m = (l + r) >> 1;
+            self.left.as_ref().map(|n| n.fold(a, b, cols, l, m)).unwrap_or_else(T::identity)
+                .op(&self.right.as_ref().map(|n| n.fold(a, b, cols, m, r)).unwrap_or_else(T::identity))
+        }
+    }
+}
+
+pub struct DynamicSegmentTree2D<T: Monoid> {
+    root: Option<Box<Node<T>>>,
+    h: usize,
+    w: usize,
+}
+
+impl<T: Monoid> DynamicSegmentTree2D<T> {
+    pub fn new(h: usize, w: usize) -> Self {
+        DynamicSegmentTree2D { root: None, h: h.next_power_of_two(), w }
+    }
+    pub fn set(&mut self, i: usize, j: usize, x: T) {
+        let w = self.w;
+        self.root.get_or_insert_with(|| Box::new(Node::new(w))).set(i, j, x, 0, self.h, w);
+    }
+    pub fn fold(&self, rows: Range<usize>, cols: Range<usize>) -> T {
+        match self.root {
+            Some(ref root) => root.fold(rows.start, rows.end, &cols, 0, self.h),
+            None => T::identity(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod dynamic_segment_tree_2d_test {
+    use crate::algebra::*;
+    use crate::data_structures::segment_tree::dynamic_segment_tree_2d::DynamicSegmentTree2D;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[derive(Clone)]
+    struct Am(u64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(4);
+        let n = 1_000_000_000;
+        let mut seg = DynamicSegmentTree2D::new(n, n);
+        let mut points = Vec::new();
+        for _ in 0..50 {
+            let (i, j, x) = ((rng.rand_u64() % n as u64) as usize, (rng.rand_u64() % n as u64) as usize, rng.rand_u64() % 100);
+            seg.set(i, j, Am(x));
+            points.retain(|&(pi, pj, _)| (pi, pj) != (i, j));
+            points.push((i, j, x));
+        }
+        for _ in 0..200 {
+            let (mut u, mut d) = ((rng.rand_u64() % n as u64) as usize, (rng.rand_u64() % n as u64) as usize);
+            let (mut l, mut r) = ((rng.rand_u64() % n as u64) as usize, (rng.rand_u64() % n as u64) as usize);
+            if u > d { std::mem::swap(&mut u, &mut d); }
+            if l > r { std::mem::swap(&mut l, &mut r); }
+            let expected = points.iter()
+                .filter(|&&(i, j, _)| u <= i && i < d && l <= j && j < r)
+                .map(|&(_, _, x)| x).sum::<u64>();
+            assert_eq!(seg.fold(u..d, l..r).0, expected);
+        }
+        assert_eq!(seg.fold(0..n, 0..n).0, points.iter().map(|p| p.2).sum::<u64>());
+    }
+}
@@ -1,5 +1,8 @@
 use crate::algebra::*;
 use crate::data_structures::set::bitset::Bitset;
+use crate::data_structures::node_seq::bounds;
+
+use std::ops::RangeBounds;
 
 #[derive(Clone)]
 struct Node<T: Clone, E: Clone> {
@@ -9,9 +12,10 @@ struct Node<T: Clone, E: Clone> {
 
 pub struct LazySegmentTree<T: Monoid + Effect<E>, E: Monoid> {
     node: Box<[Node<T, E>]>,
-    //flag: Bitset,
+    flag: Bitset,
     sz: usize,
     h: usize,
+    n: usize,
 }
 
 impl<T: Monoid + Effect<E>, E: Monoid> LazySegmentTree<T, E> {
@@ -23,27 +27,30 @@ impl<T: Monoid + Effect<E>, E: Monoid> LazySegmentTree<T, E> {
         for i in (1..sz).rev() { node[i].val = node[i << 1].val.op(&node[(i << 1) + 1].val); }
         Self {
             node: node.into_boxed_slice(),
-            //flag: Bitset::new(sz << 1),
+            flag: Bitset::new(sz),
             sz,
-            h
+            h,
+            n: arr.len(),
         }
     }
 
+
     fn effect(&mut self, i: usize, e: &E) {
         if i < self.node.len() {
-            //self.flag.set(i, true);
             self.node[i].val = self.node[i].val.effect(e);
-            self.node[i].lazy = self.node[i].lazy.op(e);
+            if i < self.sz {
+                self.node[i].lazy = self.node[i].lazy.op(e);
+                self.flag.set(i, true);
+            }
         }
     }
 
     fn push(&mut self, i: usize) {
-        //if self.flag.get(i) {
-        {
+        if i < self.sz && self.flag.get(i) {
             let e = std::mem::replace(&mut self.node[i].lazy, E::identity());
             self.effect(i << 1, &e);
             self.effect((i << 1) + 1, &e);
-            //self.flag.set(i, false);
+            self.flag.set(i, false);
         }
     }
 
@@ -63,7 +70,8 @@ impl<T: Monoid + Effect<E>, E: Monoid> LazySegmentTree<T, E> {
         }
     }
 
-    pub fn update(&mut self, a: usize, b: usize, e: E) {
+    pub fn update<R: RangeBounds<usize>>(&mut self, ran: R, e: E) {
+        let (a, b) = bounds(ran, self.n);
         let mut l = a + self.sz;
         let mut r = b + self.sz;
         self.infiltrate(l);
@@ -84,7 +92,8 @@ impl<T: Monoid + Effect<E>, E: Monoid> LazySegmentTree<T, E> {
         self.infuse(b + self.sz);
     }
 
-    pub fn fold(&mut self, a: usize, b: usize) -> T {
+    pub fn fold<R: RangeBounds<usize>>(&mut self, ran: R) -> T {
+        let (a, b) = bounds(ran, self.n);
         let mut l = a + self.sz;
         let mut r = b + self.sz;
         self.infiltrate(l);
@@ -105,6 +114,85 @@ impl<T: Monoid + Effect<E>, E: Monoid> LazySegmentTree<T, E> {
         }
         lx.op(&rx)
     }
+
+    pub fn all_fold(&self) -> T { self.node[1].val.clone() }
+
+    pub fn get(&mut self, i: usize) -> &T {
+        assert!(i < self.n);
+        let i = i + self.sz;
+        for j in (1..self.h).rev() { self.push(i >> j); }
+        &self.node[i].val
+    }
+
+    pub fn set(&mut self, i: usize, x: T) {
+        assert!(i < self.n);
+        let mut i = i + self.sz;
+        for j in (1..self.h).rev() { self.push(i >> j); }
+        self.node[i].val = x;
+        while i > 1 {
+            i >>= 1;
+            self.node[i].val = self.node[i << 1].val.op(&self.node[(i << 1) + 1].val);
+        }
+    }
+
+    pub fn to_vec(&mut self) -> Vec<T> {
+        for i in 1..self.sz { self.push(i); }
+        self.node[self.sz..self.sz + self.n].iter().map(|nd| nd.val.clone()).collect()
+    }
+
+    pub fn max_right<F>(&mut self, l: usize, pred: F) -> usize where F: Fn(&T) -> bool {
+        assert!(pred(&T::identity()));
+        if l == self.n { return self.n }
+        let mut l = l + self.sz;
+        for j in (1..self.h).rev() { self.push(l >> j); }
+        let mut sm = T::identity();
+        loop {
+            while l & 1 == 0 { l >>= 1; }
+            if !pred(&sm.op(&self.node[l].val)) {
+                while l < self.sz {
+                    self.push(l);
+                    l <<= 1;
+                    let res = sm.op(&self.node[l].val);
+                    if pred(&res) {
+                        sm = res;
+                        l += 1;
+                    }
+                }
+                return l - self.sz;
+            }
+            sm = sm.op(&self.node[l].val);
+            l += 1;
+            if l.is_power_of_two() { break }
+        }
+        self.n
+    }
+
+    pub fn min_left<F>(&mut self, r: usize, pred: F) -> usize where F: Fn(&T) -> bool {
+        assert!(pred(&T::identity()));
+        if r == 0 { return 0 }
+        let mut r = r + self.sz;
+        for j in (1..self.h).rev() { self.push((r - 1) >> j); }
+        let mut sm = T::identity();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 { r >>= 1; }
+            if !pred(&self.node[r].val.op(&sm)) {
+                while r < self.sz {
+                    self.push(r);
+                    r = (r << 1) + 1;
+                    let res = self.node[r].val.op(&sm);
+                    if pred(&res) {
+                        sm = res;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.sz;
+            }
+            sm = self.node[r].val.op(&sm);
+            if r.is_power_of_two() { break }
+        }
+        0
+    }
 }
 
 #[cfg(test)]
@@ -149,10 +237,95 @@ mod rmq_ruq_test {
     #[test]
     fn rmq_ruq_test() {
         let mut seg = LazySegmentTree::new(&vec![Mm::identity(); 3]);
-        seg.update(0, 2, Uq(Some(1)));
-        seg.update(1, 3, Uq(Some(3)));
-        seg.update(2, 3, Uq(Some(2)));
-        assert_eq!(seg.fold(0, 3).0, 1);
-        assert_eq!(seg.fold(1, 3).0, 2);
+        seg.update(0..2, Uq(Some(1)));
+        seg.update(1..3, Uq(Some(3)));
+        seg.update(2..3, Uq(Some(2)));
+        assert_eq!(seg.fold(..).0, 1);
+        assert_eq!(seg.fold(1..3).0, 2);
+        assert_eq!(seg.fold(1..=1).0, 3);
+        assert_eq!(seg.get(0).0, 1);
+        seg.set(0, Mm(5));
+        assert_eq!(seg.all_fold().0, 2);
+        assert_eq!(seg.to_vec().iter().map(|x| x.0).collect::<Vec<_>>(), vec![5, 3, 2]);
+    }
+
+    #[test]
+    fn binary_search_test() {
+        let mut seg = LazySegmentTree::new(&[Mm(5), Mm(4), Mm(7), Mm(6), Mm(8)]);
+        seg.update(1..3, Uq(Some(9)));
+        assert_eq!(seg.max_right(0, |x| x.0 >= 5), 5);
+        assert_eq!(seg.max_right(0, |x| x.0 >= 6), 0);
+        assert_eq!(seg.max_right(1, |x| x.0 >= 7), 3);
+        assert_eq!(seg.max_right(3, |x| x.0 >= 6), 5);
+        assert_eq!(seg.min_left(5, |x| x.0 >= 6), 1);
+        assert_eq!(seg.min_left(5, |x| x.0 >= 7), 4);
+        assert_eq!(seg.min_left(3, |x| x.0 >= 5), 0);
+        assert_eq!(seg.min_left(0, |x| x.0 >= 5), 0);
+    }
+}
+
+#[cfg(test)]
+mod affine_test {
+    use crate::algebra::*;
+    use crate::data_structures::segment_tree::lazy_segment_tree::LazySegmentTree;
+    use crate::random::{ Random, Xorshift128 };
+
+    const M: u64 = 998244353;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Sum(u64, u64);
+
+    impl Magma for Sum {
+        fn op(&self, right: &Self) -> Self { Sum((self.0 + right.0) % M, self.1 + right.1) }
+    }
+    impl Associative for Sum {}
+    impl Unital for Sum {
+        fn identity() -> Self { Sum(0, 0) }
+    }
+
+    #[derive(Clone)]
+    struct Affine(u64, u64);
+
+    impl Magma for Affine {
+        fn op(&self, right: &Self) -> Self { Affine(self.0 * right.0 % M, (self.1 * right.0 + right.1) % M) }
+    }
+    impl Associative for Affine {}
+    impl Unital for Affine {
+        fn identity() -> Self { Affine(1, 0) }
+    }
+    impl Effect<Affine> for Sum {
+        fn effect(&self, f: &Affine) -> Self { Sum((self.0 * f.0 + self.1 * f.1) % M, self.1) }
+    }
+
+    #[test]
+    fn affine_random_test() {
+        let mut rng = Xorshift128::new(91);
+        for n in 1..20 {
+            let mut arr = (0..n).map(|_| rng.rand_u64() % M).collect::<Vec<_>>();
+            let mut seg = LazySegmentTree::new(&arr.iter().map(|&x| Sum(x, 1)).collect::<Vec<_>>());
+            for _ in 0..100 {
+                let l = (rng.rand_u64() % (n as u64 + 1)) as usize;
+                let r = l + (rng.rand_u64() % ((n - l) as u64 + 1)) as usize;
+                match rng.rand_u64() % 3 {
+                    0 => {
+                        let (a, b) = (rng.rand_u64() % M, rng.rand_u64() % M);
+                        seg.update(l..r, Affine(a, b));
+                        for x in arr[l..r].iter_mut() { *x = (*x * a + b) % M; }
+                    }
+                    1 => {
+                        let expected = arr[l..r].iter().fold(0, |s, x| (s + x) % M);
+                        assert_eq!(seg.fold(l..r).0, expected);
+                    }
+                    _ if l < n => {
+                        let x = rng.rand_u64() % M;
+                        seg.set(l, Sum(x, 1));
+                        arr[l] = x;
+                        assert_eq!(seg.get(l).0, x);
+                    }
+                    _ => {}
+                }
+            }
+            assert_eq!(seg.to_vec(), arr.iter().map(|&x| Sum(x, 1)).collect::<Vec<_>>());
+        }
     }
 }
@@ -18,10 +18,12 @@ impl<T: Monoid> Node<T> {
     fn build(l: usize, r: usize, arr: &[T]) -> Self {
         if l + 1 >= r { Node::new(arr[l].clone()) }
         else {
+            let left = Node::build(l, (l + r) >> 1, arr);
+            let right = Node::build((l + r) >> 1, r, arr);
             Node {
-                data: T::identity(),
-                left: Some(Rc::new(Node::build(l, (l + r) >> 1, arr))),
-                right: Some(Rc::new(Node::build((l + r) >> 1, r, arr))),
+                data: left.data.op(&right.data),
+                left: Some(Rc::new(left)),
+                right: Some(Rc::new(right)),
             }
         }
     }
@@ -57,6 +59,36 @@ impl<T: Monoid> Node<T> {
                 .op(& match self.right.as_ref() { Some(n) => n.fold(a, b, (l + r) >> 1, r), None => T::identity() })
         }
     }
+    fn max_right<F>(&self, a: usize, l: usize, r: usize, acc: &mut T, pred: &F) -> Option<usize>
+    where F: Fn(&T) -> bool {
+        if r <= a { return None }
+        if a <= l {
+            let res = acc.op(&self.data);
+            if pred(&res) {
+                *acc = res;
+                return None;
+            }
+            if l + 1 == r { return Some(l) }
+        }
+        let m = (l + r) >> 1;
+        self.left.as_ref().and_then(|n| n.max_right(a, l, m, acc, pred))
+            .or_else(|| self.right.as_ref().and_then(|n| n.max_right(a, m, r, acc, pred)))
+    }
+    fn min_left<F>(&self, b: usize, l: usize, r: usize, acc: &mut T, pred: &F) -> Option<usize>
+    where F: Fn(&T) -> bool {
+        if b <= l { return None }
+        if r <= b {
+            let res = self.data.op(acc);
+            if pred(&res) {
+                *acc = res;
+                return None;
+            }
+            if l + 1 == r { return Some(r) }
+        }
+        let m = (l + r) >> 1;
+        self.right.as_ref().and_then(|n| n.min_left(b, m, r, acc, pred))
+            .or_else(|| self.left.as_ref().and_then(|n| n.min_left(b, l, m, acc, pred)))
+    }
 }
 
 impl<T: Monoid> Drop for Node<T> {
@@ -85,6 +117,16 @@ impl<T: Monoid> PersistentSegmentTree<T> {
     pub fn fold(&self, ran: Range<usize>) -> T {
         self.root.fold(ran.start, ran.end, 0, self.sz)
     }
+    pub fn max_right<F>(&self, l: usize, pred: F) -> usize where F: Fn(&T) -> bool {
+        assert!(pred(&T::identity()));
+        let mut acc = T::identity();
+        self.root.max_right(l, 0, self.sz, &mut acc, &pred).unwrap_or(self.sz)
+    }
+    pub fn min_left<F>(&self, r: usize, pred: F) -> usize where F: Fn(&T) -> bool {
+        assert!(pred(&T::identity()));
+        let mut acc = T::identity();
+        self.root.min_left(r, 0, self.sz, &mut acc, &pred).unwrap_or(0)
+    }
 }
 
 #[cfg(test)]
@@ -108,5 +150,18 @@ mod rsq_test {
         let seg = PersistentSegmentTree::new(&[Am(1), Am(2), Am(3)]);
         assert_eq!(seg.fold(0..2).0, 3);
         assert_eq!(seg.fold(1..2).0, 2);
+        assert_eq!(seg.fold(1..3).0, 5);
+    }
+    #[test]
+    fn binary_search_test() {
+        let seg = PersistentSegmentTree::new(&[Am(3), Am(1), Am(4), Am(1), Am(5)]);
+        let seg2 = seg.set(2, Am(0));
+        assert_eq!(seg.max_right(0, |x| x.0 <= 7), 2);
+        assert_eq!(seg2.max_right(0, |x| x.0 <= 7), 4);
+        assert_eq!(seg2.max_right(0, |x| x.0 <= 10), 5);
+        assert_eq!(seg.max_right(2, |x| x.0 <= 3), 2);
+        assert_eq!(seg.min_left(5, |x| x.0 <= 6), 3);
+        assert_eq!(seg2.min_left(5, |x| x.0 <= 6), 2);
+        assert_eq!(seg2.min_left(3, |x| x.0 <= 100), 0);
     }
 }
@@ -0,0 +1,110 @@
+use crate::algebra::*;
+use crate::data_structures::segment_tree::segment_tree::SegmentTree;
+
+use std::ops::Range;
+
+pub struct RangeTree<K: Ord + Clone, T: Monoid> {
+    xs: Vec<K>,
+    ys: Vec<Vec<(K, K)>>,
+    seg: Vec<SegmentTree<T>>,
+    sz: usize,
+}
+
+impl<K: Ord + Clone, T: Monoid> RangeTree<K, T> {
+    pub fn new(points: &[(K, K)]) -> Self {
+        let mut xs = points.iter().map(|p| p.0.clone()).collect::<Vec<_>>();
+        xs.sort();
+        xs.dedup();
+        let sz = xs.len().next_power_of_two();
+        let mut ys = vec![Vec::new(); sz << 1];
+        for (x, y) in points.iter().cloned() {
+            let i = xs.binary_search(&x).unwrap();
+            ys[i + sz].push((y, x));
+        }
+        for v in ys[sz..].iter_mut() {
+            v.sort();
+            v.dedup();
+        }
+        for i in (1..sz).rev() {
+            let mut v = ys[i << 1].clone();
+            v.extend(ys[(i << 1) + 1].iter().cloned());
+            v.sort();
+            ys[i] = v;
+        }
+        let seg = ys.iter().map(|v| SegmentTree::init(&vec![T::identity(); v.len()])).collect();
+        RangeTree { xs, ys, seg, sz }
+    }
+
+    pub fn set(&mut self, x: K, y: K, val: T) {
+        let mut i = self.xs.binary_search(&x).expect("the point is not registered") + self.sz;
+        let key = (y, x);
+        while i >= 1 {
+            let j = self.ys[i].binary_search(&key).expect("the point is not registered");
+            self.seg[i].set(j, val.clone());
+            i >>= 1;
+        }
+    }
+
+    fn fold_node(&self, i: usize, yr: &Range<K>) -> T {
+        let l = self.ys[i].partition_point(|p| p.0 < yr.start);
+        let r = self.ys[i].partition_point(|p| p.0 < yr.end);
+        self.seg[i].fold(l..r)
+    }
+
+    pub fn fold(&self, xr: Range<K>, yr: Range<K>) -> T {
+        let mut lx = T::identity();
+        let mut rx = T::identity();
+        let mut l = self.xs.partition_point(|x| *x < xr.start) + self.sz;
+        let mut r = self.xs.partition_point(|x| *x < xr.end) + self.sz;
+        while l < r {
+            if l & 1 == 1 { lx = lx.op(&self.fold_node(l, &yr)); l += 1; }
+            if r & 1 == 1 { r -= 1; rx = self.fold_node(r, &yr).op(&rx); }
+            l >>= 1;
+            r >>= 1;
+        }
+        lx.op(&rx)
+    }
+}
+
+#[cfg(test)]
+mod range_tree_test {
+    use crate::algebra::*;
+    use crate::data_structures::segment_tree::range_tree::RangeTree;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[derive(Clone)]
+    struct Am(i64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(5);
+        let mut points = (0..100).map(|_| ((rng.rand_u64() % 30) as i64 - 15, (rng.rand_u64() % 30) as i64 - 15, 0)).collect::<Vec<_>>();
+        let mut tree = RangeTree::new(&points.iter().map(|p| (p.0, p.1)).collect::<Vec<_>>());
+        for _ in 0..300 {
+            let k = (rng.rand_u64() % points.len() as u64) as usize;
+            let v = (rng.rand_u64() % 100) as i64;
+            let (x, y) = (points[k].0, points[k].1);
+            for p in points.iter_mut().filter(|p| (p.0, p.1) == (x, y)) { p.2 = v; }
+            tree.set(x, y, Am(v));
+
+            let (mut xl, mut xr) = ((rng.rand_u64() % 34) as i64 - 17, (rng.rand_u64() % 34) as i64 - 17);
+            let (mut yl, mut yr) = ((rng.rand_u64() % 34) as i64 - 17, (rng.rand_u64() % 34) as i64 - 17);
+            if xl > xr { std::mem::swap(&mut xl, &mut xr); }
+            if yl > yr { std::mem::swap(&mut yl, &mut yr); }
+            let mut expected = points.iter()
+                .filter(|p| xl <= p.0 && p.0 < xr && yl <= p.1 && p.1 < yr)
+                .map(|p| (p.0, p.1, p.2)).collect::<Vec<_>>();
+            expected.sort();
+            expected.dedup();
+            assert_eq!(tree.fold(xl..xr, yl..yr).0, expected.iter().map(|p| p.2).sum::<i64>());
+        }
+    }
+}
@@ -5,6 +5,7 @@ use std::ops::Range;
 pub struct SegmentTree<T: Monoid> {
     node: Vec<T>,
     sz: usize,
+    n: usize,
 }
 
 impl<T: Monoid> SegmentTree<T> {
@@ -14,7 +15,7 @@ impl<T: Monoid> SegmentTree<T> {
         let mut node = vec![T::identity(); sz << 1];
         for i in 0..arr.len() { node[i + sz] = arr[i].clone(); }
         for i in (1..sz).rev() { node[i] = node[i << 1].op(&node[(i << 1) + 1]); }
-        SegmentTree { node: node, sz: sz }
+        SegmentTree { node: node, sz: sz, n: arr.len() }
     }
 
     fn fix(&mut self, i: usize) {
@@ -25,7 +26,7 @@ impl<T: Monoid> SegmentTree<T> {
         }
     }
 
-    pub fn get(&self, i: usize) -> &T { &self.node[i] }
+    pub fn get(&self, i: usize) -> &T { &self.node[i + self.sz] }
 
     pub fn set(&mut self, i: usize, x: T) {
         let idx = i + self.sz;
@@ -52,6 +53,56 @@ impl<T: Monoid> SegmentTree<T> {
         }
         lx.op(&rx)
     }
+
+    pub fn max_right<F>(&self, l: usize, pred: F) -> usize where F: Fn(&T) -> bool {
+        assert!(pred(&T::identity()));
+        if l == self.n { return self.n }
+        let mut l = l + self.sz;
+        let mut sm = T::identity();
+        loop {
+            while l & 1 == 0 { l >>= 1; }
+            if !pred(&sm.op(&self.node[l])) {
+                while l < self.sz {
+                    l <<= 1;
+                    let res = sm.op(&self.node[l]);
+                    if pred(&res) {
+                        sm = res;
+                        l += 1;
+                    }
+                }
+                return l - self.sz;
+            }
+            sm = sm.op(&self.node[l]);
+            l += 1;
+            if l.is_power_of_two() { break }
+        }
+        self.n
+    }
+
+    pub fn min_left<F>(&self, r: usize, pred: F) -> usize where F: Fn(&T) -> bool {
+        assert!(pred(&T::identity()));
+        if r == 0 { return 0 }
+        let mut r = r + self.sz;
+        let mut sm = T::identity();
+        loop {
+            r -= 1;
+            while r > 1 && r & 1 == 1 { r >>= 1; }
+            if !pred(&self.node[r].op(&sm)) {
+                while r < self.sz {
+                    r = (r << 1) + 1;
+                    let res = self.node[r].op(&sm);
+                    if pred(&res) {
+                        sm = res;
+                        r -= 1;
+                    }
+                }
+                return r + 1 - self.sz;
+            }
+            sm = self.node[r].op(&sm);
+            if r.is_power_of_two() { break }
+        }
+        0
+    }
 }
 
 #[cfg(test)]
@@ -83,6 +134,26 @@ mod rsq_test {
         assert!(seg.fold(1..2).0 == 10);
     }
     #[test]
+    fn binary_search_test() {
+        let arr = [3, 1, 4, 1, 5, 9, 2, 6];
+        let seg = SegmentTree::init(&arr.iter().map(|&x| Am(x)).collect::<Vec<_>>());
+        for l in 0..arr.len() + 1 {
+            for k in 0..30 {
+                let mut r = l;
+                while r < arr.len() && arr[l..r + 1].iter().sum::<usize>() <= k { r += 1; }
+                assert_eq!(seg.max_right(l, |x| x.0 <= k), r);
+            }
+        }
+        for r in 0..arr.len() + 1 {
+            for k in 0..30 {
+                let mut l = r;
+                while l > 0 && arr[l - 1..r].iter().sum::<usize>() <= k { l -= 1; }
+                assert_eq!(seg.min_left(r, |x| x.0 <= k), l);
+            }
+        }
+        assert_eq!(seg.get(2).0, 4);
+    }
+    #[test]
     fn corner_test() {
         let seg = SegmentTree::init(&vec![Am(1)]);
         assert!(seg.fold(0..1).0 == 1);
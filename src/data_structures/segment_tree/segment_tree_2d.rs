@@ -0,0 +1,128 @@
+use crate::algebra::*;
+
+use std::ops::Range;
+
+pub struct SegmentTree2D<T: Monoid> {
+    node: Vec<T>,
+    h: usize,
+    w: usize,
+}
+
+impl<T: Monoid> SegmentTree2D<T> {
+    pub fn init(arr: &[Vec<T>]) -> Self {
+        let h = arr.len().next_power_of_two();
+        let w = arr.iter().map(|row| row.len()).max().unwrap_or(0).next_power_of_two();
+        let mut node = vec![T::identity(); (h << 1) * (w << 1)];
+        for (i, row) in arr.iter().enumerate() {
+            for (j, x) in row.iter().enumerate() {
+                node[(i + h) * (w << 1) + j + w] = x.clone();
+            }
+        }
+        let mut seg = SegmentTree2D { node, h, w };
+        for i in h..(h << 1) {
+            for j in (1..w).rev() {
+                seg.pull(seg.idx(i, j), seg.idx(i, j << 1), seg.idx(i, (j << 1) + 1));
+            }
+        }
+        for i in (1..h).rev() {
+            for j in 1..(w << 1) {
+                seg.pull(seg.idx(i, j), seg.idx(i << 1, j), seg.idx((i << 1) + 1, j));
+            }
+        }
+        seg
+    }
+
+    fn idx(&self, i: usize, j: usize) -> usize { i * (self.w << 1) + j }
+
+    fn pull(&mut self, k: usize, a: usize, b: usize) {
+        self.node[k] = self.node[a].op(&self.node[b]);
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> &T { &self.node[self.idx(i + self.h, j + self.w)] }
+
+    pub fn set(&mut self, i: usize, j: usize, x: T) {
+        let (i, j) = (i + self.h, j + self.w);
+        let k = self.idx(i, j);
+        self.node[k] = x;
+        let mut jj = j >> 1;
+        while jj >= 1 {
+            self.pull(self.idx(i, jj), self.idx(i, jj << 1), self.idx(i, (jj << 1) + 1));
+            jj >>= 1;
+        }
+        let mut ii = i >> 1;
+        while ii >= 1 {
+            let mut jj = j;
+            while jj >= 1 {
+                self.pull(self.idx(ii, jj), self.idx(ii << 1, jj), self.idx((ii << 1) + 1, jj));
+                jj >>= 1;
+            }
+            ii >>= 1;
+        }
+    }
+
+    fn fold_row(&self, i: usize, cols: &Range<usize>) -> T {
+        let mut lx = T::identity();
+        let mut rx = T::identity();
+        let mut l = cols.start + self.w;
+        let mut r = cols.end + self.w;
+        while l < r {
+            if l & 1 == 1 { lx = lx.op(&self.node[self.idx(i, l)]); l += 1; }
+            if r & 1 == 1 { r -= 1; rx = self.node[self.idx(i, r)].op(&rx); }
+            l >>= 1;
+            r >>= 1;
+        }
+        lx.op(&rx)
+    }
+
+    pub fn fold(&self, rows: Range<usize>, cols: Range<usize>) -> T {
+        let mut lx = T::identity();
+        let mut rx = T::identity();
+        let mut l = rows.start + self.h;
+        let mut r = rows.end + self.h;
+        while l < r {
+            if l & 1 == 1 { lx = lx.op(&self.fold_row(l, &cols)); l += 1; }
+            if r & 1 == 1 { r -= 1; rx = self.fold_row(r, &cols).op(&rx); }
+            l >>= 1;
+            r >>= 1;
+        }
+        lx.op(&rx)
+    }
+}
+
+#[cfg(test)]
+mod segment_tree_2d_test {
+    use crate::algebra::*;
+    use crate::data_structures::segment_tree::segment_tree_2d::SegmentTree2D;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[derive(Clone)]
+    struct Am(u64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(3);
+        let (h, w) = (5, 7);
+        let mut arr = (0..h).map(|_| (0..w).map(|_| rng.rand_u64() % 100).collect::<Vec<_>>()).collect::<Vec<_>>();
+        let mut seg = SegmentTree2D::init(&arr.iter().map(|row| row.iter().map(|&x| Am(x)).collect()).collect::<Vec<_>>());
+        for _ in 0..500 {
+            let (i, j, x) = ((rng.rand_u64() % h as u64) as usize, (rng.rand_u64() % w as u64) as usize, rng.rand_u64() % 100);
+            seg.set(i, j, Am(x));
+            arr[i][j] = x;
+            let u = (rng.rand_u64() % (h as u64 + 1)) as usize;
+            let d = u + (rng.rand_u64() % ((h - u) as u64 + 1)) as usize;
+            let l = (rng.rand_u64() % (w as u64 + 1)) as usize;
+            let r = l + (rng.rand_u64() % ((w - l) as u64 + 1)) as usize;
+            let expected = arr[u..d].iter().map(|row| row[l..r].iter().sum::<u64>()).sum::<u64>();
+            assert_eq!(seg.fold(u..d, l..r).0, expected);
+            assert_eq!(seg.get(i, j).0, x);
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::data_structures::node_seq::bounds;
+
+use std::ops::RangeBounds;
+
+#[derive(Clone)]
+struct Node {
+    max1: i64,
+    max2: i64,
+    maxc: usize,
+    min1: i64,
+    min2: i64,
+    minc: usize,
+    sum: i64,
+    len: usize,
+    add: i64,
+}
+
+impl Node {
+    fn leaf(x: i64) -> Self {
+        Node { max1: x, max2: i64::MIN, maxc: 1, min1: x, min2: i64::MAX, minc: 1, sum: x, len: 1, add: 0 }
+    }
+
+    fn merge(l: &Node, r: &Node) -> Self {
+        let (max1, max2, maxc) = match l.max1.cmp(&r.max1) {
+            std::cmp::Ordering::Equal => (l.max1, std::cmp::max(l.max2, r.max2), l.maxc + r.maxc),
+            std::cmp::Ordering::Greater => (l.max1, std::cmp::max(l.max2, r.max1), l.maxc),
+            std::cmp::Ordering::Less => (r.max1, std::cmp::max(l.max1, r.max2), r.maxc),
+        };
+        let (min1, min2, minc) = match l.min1.cmp(&r.min1) {
+            std::cmp::Ordering::Equal => (l.min1, std::cmp::min(l.min2, r.min2), l.minc + r.minc),
+            std::cmp::Ordering::Less => (l.min1, std::cmp::min(l.min2, r.min1), l.minc),
+            std::cmp::Ordering::Greater => (r.min1, std::cmp::min(l.min1, r.min2), r.minc),
+        };
+        Node { max1, max2, maxc, min1, min2, minc, sum: l.sum + r.sum, len: l.len + r.len, add: 0 }
+    }
+
+    // requires max2 < x < max1
+    fn chmin(&mut self, x: i64) {
+        self.sum += (x - self.max1) * self.maxc as i64;
+        if self.max1 == self.min1 {
+            self.min1 = x;
+        }
+        else if self.max1 == self.min2 {
+            self.min2 = x;
+        }
+        self.max1 = x;
+    }
+
+    // requires min1 < x < min2
+    fn chmax(&mut self, x: i64) {
+        self.sum += (x - self.min1) * self.minc as i64;
+        if self.min1 == self.max1 {
+            self.max1 = x;
+        }
+        else if self.min1 == self.max2 {
+            self.max2 = x;
+        }
+        self.min1 = x;
+    }
+
+    fn add(&mut self, x: i64) {
+        self.max1 += x;
+        if self.max2 != i64::MIN { self.max2 += x; }
+        self.min1 += x;
+        if self.min2 != i64::MAX { self.min2 += x; }
+        self.sum += x * self.len as i64;
+        self.add += x;
+    }
+}
+
+pub struct SegmentTreeBeats {
+    node: Vec<Node>,
+    n: usize,
+}
+
+impl SegmentTreeBeats {
+    pub fn new(arr: &[i64]) -> Self {
+        let mut seg = SegmentTreeBeats {
+            node: vec![Node::leaf(0); std::cmp::max(arr.len(), 1) << 2],
+            n: arr.len(),
+        };
+        if seg.n > 0 { seg.build(1, 0, seg.n, arr); }
+        seg
+    }
+
+    fn build(&mut self, k: usize, l: usize, r: usize, arr: &[i64]) {
+        if l + 1 == r {
+            self.node[k] = Node::leaf(arr[l]);
+        }
+        else {
+            let m = (l + r) >> 1;
+            self.build(k << 1, l, m, arr);
+            self.build((k << 1) + 1, m, r, arr);
+            self.fix(k);
+        }
+    }
+
+    fn fix(&mut self, k: usize) {
+        self.node[k] = Node::merge(&self.node[k << 1], &self.node[(k << 1) + 1]);
+    }
+
+    fn push(&mut self, k: usize) {
+        let add = std::mem::replace(&mut self.node[k].add, 0);
+        let (max1, min1) = (self.node[k].max1, self.node[k].min1);
+        for c in (k << 1)..(k << 1) + 2 {
+            if add != 0 { self.node[c].add(add); }
+            if max1 < self.node[c].max1 { self.node[c].chmin(max1); }
+            if self.node[c].min1 < min1 { self.node[c].chmax(min1); }
+        }
+    }
+
+    fn chmin_rec(&mut self, a: usize, b: usize, x: i64, k: usize, l: usize, r: usize) {
+        if b <= l || r <= a || self.node[k].max1 <= x { return }
+        if a <= l && r <= b && self.node[k].max2 < x {
+            self.node[k].chmin(x);
+            return
+        }
+        self.push(k);
+        let m = (l + r) >> 1;
+        self.chmin_rec(a, b, x, k << 1, l, m);
+        self.chmin_rec(a, b, x, (k << 1) + 1, m, r);
+        self.fix(k);
+    }
+
+    fn chmax_rec(&mut self, a: usize, b: usize, x: i64, k: usize, l: usize, r: usize) {
+        if b <= l || r <= a || x <= self.node[k].min1 { return }
+        if a <= l && r <= b && x < self.node[k].min2 {
+            self.node[k].chmax(x);
+            return
+        }
+        self.push(k);
+        let m = (l + r) >> 1;
+        self.chmax_rec(a, b, x, k << 1, l, m);
+        self.chmax_rec(a, b, x, (k << 1) + 1, m, r);
+        self.fix(k);
+    }
+
+    fn add_rec(&mut self, a: usize, b: usize, x: i64, k: usize, l: usize, r: usize) {
+        if b <= l || r <= a { return }
+        if a <= l && r <= b {
+            self.node[k].add(x);
+            return
+        }
+        self.push(k);
+        let m = (l + r) >> 1;
+        self.add_rec(a, b, x, k << 1, l, m);
+        self.add_rec(a, b, x, (k << 1) + 1, m, r);
+        self.fix(k);
+    }
+
+    fn fold_rec(&mut self, a: usize, b: usize, k: usize, l: usize, r: usize) -> Option<Node> {
+        if b <= l || r <= a { return None }
+        if a <= l && r <= b { return Some(self.node[k].clone()) }
+        self.push(k);
+        let m = (l + r) >> 1;
+        match (self.fold_rec(a, b, k << 1, l, m), self.fold_rec(a, b, (k << 1) + 1, m, r)) {
+            (Some(lx), Some(rx)) => Some(Node::merge(&lx, &rx)),
+            (lx, rx) => lx.or(rx),
+        }
+    }
+
+    pub fn chmin<R: RangeBounds<usize>>(&mut self, ran: R, x: i64) {
+        let (a, b) = bounds(ran, self.n);
+        if self.n > 0 { self.chmin_rec(a, b, x, 1, 0, self.n); }
+    }
+
+    pub fn chmax<R: RangeBounds<usize>>(&mut self, ran: R, x: i64) {
+        let (a, b) = bounds(ran, self.n);
+        if self.n > 0 { self.chmax_rec(a, b, x, 1, 0, self.n); }
+    }
+
+    pub fn add<R: RangeBounds<usize>>(&mut self, ran: R, x: i64) {
+        let (a, b) = bounds(ran, self.n);
+        if self.n > 0 { self.add_rec(a, b, x, 1, 0, self.n); }
+    }
+
+    pub fn assign<R: RangeBounds<usize>>(&mut self, ran: R, x: i64) {
+        let (a, b) = bounds(ran, self.n);
+        self.chmin(a..b, x);
+        self.chmax(a..b, x);
+    }
+
+    fn fold<R: RangeBounds<usize>>(&mut self, ran: R) -> Option<Node> {
+        let (a, b) = bounds(ran, self.n);
+        if self.n == 0 { return None }
+        self.fold_rec(a, b, 1, 0, self.n)
+    }
+
+    pub fn sum<R: RangeBounds<usize>>(&mut self, ran: R) -> i64 {
+        self.fold(ran).map(|nd| nd.sum).unwrap_or(0)
+    }
+
+    pub fn min<R: RangeBounds<usize>>(&mut self, ran: R) -> i64 {
+        self.fold(ran).map(|nd| nd.min1).unwrap_or(i64::MAX)
+    }
+
+    pub fn max<R: RangeBounds<usize>>(&mut self, ran: R) -> i64 {
+        self.fold(ran).map(|nd| nd.max1).unwrap_or(i64::MIN)
+    }
+}
+
+#[cfg(test)]
+mod segment_tree_beats_test {
+    use crate::data_structures::segment_tree::segment_tree_beats::SegmentTreeBeats;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(2020);
+        for n in 1..30 {
+            let mut arr = (0..n).map(|_| (rng.rand_u64() % 201) as i64 - 100).collect::<Vec<_>>();
+            let mut seg = SegmentTreeBeats::new(&arr);
+            for _ in 0..300 {
+                let l = (rng.rand_u64() % n as u64) as usize;
+                let r = l + 1 + (rng.rand_u64() % (n - l) as u64) as usize;
+                let x = (rng.rand_u64() % 201) as i64 - 100;
+                match rng.rand_u64() % 7 {
+                    0 => {
+                        seg.chmin(l..r, x);
+                        for a in arr[l..r].iter_mut() { *a = std::cmp::min(*a, x); }
+                    }
+                    1 => {
+                        seg.chmax(l..r, x);
+                        for a in arr[l..r].iter_mut() { *a = std::cmp::max(*a, x); }
+                    }
+                    2 => {
+                        seg.add(l..r, x);
+                        for a in arr[l..r].iter_mut() { *a += x; }
+                    }
+                    3 => {
+                        seg.assign(l..r, x);
+                        for a in arr[l..r].iter_mut() { *a = x; }
+                    }
+                    4 => assert_eq!(seg.sum(l..r), arr[l..r].iter().sum::<i64>()),
+                    5 => assert_eq!(seg.min(l..r), *arr[l..r].iter().min().unwrap()),
+                    _ => assert_eq!(seg.max(l..=r - 1), *arr[l..r].iter().max().unwrap()),
+                }
+            }
+            assert_eq!(seg.sum(..), arr.iter().sum::<i64>());
+            assert_eq!(seg.min(1..), *arr[1..].iter().min().unwrap_or(&i64::MAX));
+        }
+    }
+}
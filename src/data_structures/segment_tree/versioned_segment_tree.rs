@@ -0,0 +1,214 @@
+use crate::algebra::*;
+
+use std::ops::Range;
+
+const NIL: usize = usize::MAX;
+
+struct Node<T> {
+    data: T,
+    ch: [usize; 2],
+}
+
+struct Version {
+    root: usize,
+    nodes: usize,
+}
+
+pub struct VersionedSegmentTree<T: Monoid> {
+    node: Vec<Node<T>>,
+    ver: Vec<Version>,
+    sz: usize,
+}
+
+impl<T: Monoid> VersionedSegmentTree<T> {
+    pub fn new(arr: &[T]) -> Self {
+        let mut seg = VersionedSegmentTree { node: Vec::new(), ver: Vec::new(), sz: arr.len() };
+        let root = if arr.is_empty() { NIL } else { seg.build(0, arr.len(), arr) };
+        seg.ver.push(Version { root, nodes: seg.node.len() });
+        seg
+    }
+
+    fn alloc(&mut self, data: T, ch: [usize; 2]) -> usize {
+        self.node.push(Node { data, ch });
+        self.node.len() - 1
+    }
+
+    fn build(&mut self, l: usize, r: usize, arr: &[T]) -> usize {
+        if l + 1 == r { self.alloc(arr[l].clone(), [NIL, NIL]) }
+        else {
+            let m = (l + r) >> 1;
+            let left = self.build(l, m, arr);
+            let right = self.build(m, r, arr);
+            self.alloc(self.node[left].data.op(&self.node[right].data), [left, right])
+        }
+    }
+
+    fn update(&mut self, k: usize, i: usize, x: T, l: usize, r: usize) -> usize {
+        if l + 1 == r { return self.alloc(x, [NIL, NIL]) }
+        let m = (l + r) >> 1;
+        let mut ch = self.node[k].ch;
+        if i < m { ch[0] = self.update(ch[0], i, x, l, m); }
+        else { ch[1] = self.update(ch[1], i, x, m, r); }
+        self.alloc(self.node[ch[0]].data.op(&self.node[ch[1]].data), ch)
+    }
+
+    fn fold_rec(&self, k: usize, a: usize, b: usize, l: usize, r: usize) -> T {
+        if b <= l || r <= a { T::identity() }
+        else if a <= l && r <= b { self.node[k].data.clone() }
+        else {
+            let m = (l + r) >> 1;
+            self.fold_rec(self.node[k].ch[0], a, b, l, m).op(&self.fold_rec(self.node[k].ch[1], a, b, m, r))
+        }
+    }
+
+    fn max_right_rec<F>(&self, k: usize, a: usize, l: usize, r: usize, acc: &mut T, pred: &F) -> Option<usize>
+    where F: Fn(&T) -> bool {
+        if r <= a { return None }
+        if a <= l {
+            let res = acc.op(&self.node[k].data);
+            if pred(&res) {
+                *acc = res;
+                return None;
+            }
+            if l + 1 == r { return Some(l) }
+        }
+        let m = (l + r) >> 1;
+        self.max_right_rec(self.node[k].ch[0], a, l, m, acc, pred)
+            .or_else(|| self.max_right_rec(self.node[k].ch[1], a, m, r, acc, pred))
+    }
+
+    pub fn versions(&self) -> usize { self.ver.len() }
+
+    pub fn latest(&self) -> usize { self.ver.len() - 1 }
+
+    pub fn set(&mut self, v: usize, i: usize, x: T) -> usize {
+        assert!(i < self.sz);
+        let root = self.update(self.ver[v].root, i, x, 0, self.sz);
+        self.ver.push(Version { root, nodes: self.node.len() });
+        self.ver.len() - 1
+    }
+
+    pub fn fork(&mut self, v: usize) -> usize {
+        self.ver.push(Version { root: self.ver[v].root, nodes: self.node.len() });
+        self.ver.len() - 1
+    }
+
+    pub fn rollback(&mut self, v: usize) {
+        self.ver.truncate(v + 1);
+        let nodes = self.ver[v].nodes;
+        self.node.truncate(nodes);
+    }
+
+    pub fn fold(&self, v: usize, ran: Range<usize>) -> T {
+        self.fold_rec(self.ver[v].root, ran.start, ran.end, 0, self.sz)
+    }
+
+    pub fn max_right<F>(&self, v: usize, l: usize, pred: F) -> usize where F: Fn(&T) -> bool {
+        assert!(pred(&T::identity()));
+        let mut acc = T::identity();
+        self.max_right_rec(self.ver[v].root, l, 0, self.sz, &mut acc, &pred).unwrap_or(self.sz)
+    }
+
+    // the largest `r` such that `pred` holds for the fold of `0..r` in version `hi` minus
+    // the one in version `lo`, assuming `pred` is monotone
+    pub fn max_right_diff<F>(&self, lo: usize, hi: usize, pred: F) -> usize
+    where T: Inv + Commutative, F: Fn(&T) -> bool {
+        assert!(pred(&T::identity()));
+        if self.sz == 0 { return 0 }
+        let (mut a, mut b) = (self.ver[lo].root, self.ver[hi].root);
+        if pred(&self.node[a].data.inv().op(&self.node[b].data)) { return self.sz }
+        let (mut l, mut r) = (0, self.sz);
+        let mut acc = T::identity();
+        while l + 1 < r {
+            let m = (l + r) >> 1;
+            let (al, bl) = (self.node[a].ch[0], self.node[b].ch[0]);
+            let res = acc.op(&self.node[al].data.inv().op(&self.node[bl].data));
+            if pred(&res) {
+                acc = res;
+                a = self.node[a].ch[1];
+                b = self.node[b].ch[1];
+                l = m;
+            }
+            else {
+                a = al;
+                b = bl;
+                r = m;
+            }
+        }
+        l
+    }
+}
+
+#[cfg(test)]
+mod versioned_segment_tree_test {
+    use crate::algebra::*;
+    use crate::data_structures::segment_tree::versioned_segment_tree::VersionedSegmentTree;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[derive(Clone)]
+    struct Am(i64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+    impl Inv for Am {
+        fn inv(&self) -> Self { Am(-self.0) }
+    }
+    impl Commutative for Am {}
+
+    #[test]
+    fn version_test() {
+        let mut seg = VersionedSegmentTree::new(&[Am(1), Am(2), Am(3), Am(4)]);
+        let v1 = seg.set(0, 1, Am(10));
+        let v2 = seg.set(v1, 3, Am(0));
+        let v3 = seg.fork(0);
+        let v4 = seg.set(v3, 0, Am(-1));
+        assert_eq!(seg.fold(0, 0..4).0, 10);
+        assert_eq!(seg.fold(v1, 0..4).0, 18);
+        assert_eq!(seg.fold(v2, 0..4).0, 14);
+        assert_eq!(seg.fold(v4, 0..2).0, 1);
+        assert_eq!(seg.max_right(v2, 1, |x| x.0 <= 12), 2);
+        assert_eq!(seg.max_right(v2, 1, |x| x.0 <= 13), 4);
+        seg.rollback(v2);
+        assert_eq!(seg.versions(), 3);
+        let v3 = seg.set(v2, 0, Am(5));
+        assert_eq!(seg.fold(v3, 0..4).0, 18);
+        assert_eq!(seg.fold(v1, 0..4).0, 18);
+    }
+
+    #[test]
+    fn empty_test() {
+        let mut seg = VersionedSegmentTree::<Am>::new(&[]);
+        let v = seg.fork(0);
+        assert_eq!(seg.fold(v, 0..0).0, 0);
+        assert_eq!(seg.max_right(v, 0, |x| x.0 <= 0), 0);
+        assert_eq!(seg.max_right_diff(0, v, |x| x.0 <= 0), 0);
+    }
+
+    #[test]
+    fn kth_smallest_test() {
+        let mut rng = Xorshift128::new(10);
+        let n = 50;
+        let arr = (0..n).map(|_| (rng.rand_u64() % 20) as usize).collect::<Vec<_>>();
+        let mut seg = VersionedSegmentTree::new(&vec![Am(0); 20]);
+        let mut ver = vec![0];
+        for &a in arr.iter() {
+            let last = *ver.last().unwrap();
+            let c = seg.fold(last, a..a + 1).0;
+            ver.push(seg.set(last, a, Am(c + 1)));
+        }
+        for l in 0..n {
+            for r in l + 1..n + 1 {
+                let mut sorted = arr[l..r].to_vec();
+                sorted.sort();
+                for (k, &x) in sorted.iter().enumerate() {
+                    assert_eq!(seg.max_right_diff(ver[l], ver[r], |s| s.0 <= k as i64), x);
+                }
+            }
+        }
+    }
+}
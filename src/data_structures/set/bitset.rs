@@ -1,3 +1,196 @@
+use std::ops::{ BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, ShlAssign, Shr, ShrAssign };
+
+fn shl_words(b: &mut [u64], k: usize) {
+    let (ws, bs) = (k >> 6, k & 63);
+    for i in (0..b.len()).rev() {
+        let hi = if i >= ws { b[i - ws] << bs } else { 0 };
+        let lo = if bs > 0 && i > ws { b[i - ws - 1] >> (64 - bs) } else { 0 };
+        b[i] = hi | lo;
+    }
+}
+
+fn shr_words(b: &mut [u64], k: usize) {
+    let (ws, bs) = (k >> 6, k & 63);
+    let n = b.len();
+    for i in 0..n {
+        let lo = if i + ws < n { b[i + ws] >> bs } else { 0 };
+        let hi = if bs > 0 && i + ws + 1 < n { b[i + ws + 1] << (64 - bs) } else { 0 };
+        b[i] = hi | lo;
+    }
+}
+
+// the first set bit at or after `i`
+fn find_from(b: &[u64], i: usize) -> Option<usize> {
+    let mut w = i >> 6;
+    if w >= b.len() { return None }
+    let mut cur = b[w] & (!0u64 << (i & 63));
+    while cur == 0 {
+        w += 1;
+        if w >= b.len() { return None }
+        cur = b[w];
+    }
+    Some((w << 6) + cur.trailing_zeros() as usize)
+}
+
+// iterator over the positions of the set bits
+pub struct Ones<'a> {
+    b: &'a [u64],
+    w: usize,
+    cur: u64,
+}
+
+impl<'a> Ones<'a> {
+    fn new(b: &'a [u64]) -> Self {
+        Ones { b, w: 0, cur: b.first().cloned().unwrap_or(0) }
+    }
+}
+
+impl<'a> Iterator for Ones<'a> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        while self.cur == 0 {
+            self.w += 1;
+            if self.w >= self.b.len() { return None }
+            self.cur = self.b[self.w];
+        }
+        let t = self.cur.trailing_zeros() as usize;
+        self.cur &= self.cur - 1;
+        Some((self.w << 6) + t)
+    }
+}
+
+// the operations shared by `Bitset` and `FixedBitset`, over the words `b` and `len()`
+macro_rules! impl_bitset {
+    ([$($g:tt)*] $t:ty) => {
+        impl<$($g)*> $t {
+            pub fn set(&mut self, i: usize, v: bool) {
+                assert!(i < self.len());
+                match v {
+                    true => self.b[i / 64] |= 1u64 << (i & 63),
+                    false => self.b[i / 64] &= !(1u64 << (i & 63)),
+                }
+            }
+
+            pub fn get(&self, i: usize) -> bool {
+                assert!(i < self.len());
+                (self.b[i / 64] & (1u64 << (i & 63))) > 0
+            }
+
+            pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+            // bit `i` is bit `i & 63` of word `i / 64`. the bits beyond `len` are 0
+            pub fn words(&self) -> &[u64] { &self.b }
+
+            pub fn count_ones(&self) -> usize {
+                self.b.iter().map(|w| w.count_ones() as usize).sum()
+            }
+
+            pub fn any(&self) -> bool { self.b.iter().any(|&w| w != 0) }
+
+            pub fn find_first(&self) -> Option<usize> { find_from(&self.b, 0) }
+
+            // the first set bit after `i`
+            pub fn find_next(&self, i: usize) -> Option<usize> { find_from(&self.b, i + 1) }
+
+            pub fn iter(&self) -> Ones<'_> { Ones::new(&self.b) }
+
+            fn trim(&mut self) {
+                let n = self.len();
+                if n & 63 != 0 { self.b[n >> 6] &= (1u64 << (n & 63)) - 1; }
+            }
+        }
+
+        impl<$($g)*> BitAndAssign<&$t> for $t {
+            fn bitand_assign(&mut self, rhs: &$t) {
+                assert_eq!(self.len(), rhs.len());
+                for (a, b) in self.b.iter_mut().zip(rhs.b.iter()) { *a &= *b; }
+            }
+        }
+
+        impl<$($g)*> BitOrAssign<&$t> for $t {
+            fn bitor_assign(&mut self, rhs: &$t) {
+                assert_eq!(self.len(), rhs.len());
+                for (a, b) in self.b.iter_mut().zip(rhs.b.iter()) { *a |= *b; }
+            }
+        }
+
+        impl<$($g)*> BitXorAssign<&$t> for $t {
+            fn bitxor_assign(&mut self, rhs: &$t) {
+                assert_eq!(self.len(), rhs.len());
+                for (a, b) in self.b.iter_mut().zip(rhs.b.iter()) { *a ^= *b; }
+            }
+        }
+
+        impl<$($g)*> ShlAssign<usize> for $t {
+            fn shl_assign(&mut self, k: usize) {
+                shl_words(&mut self.b, k);
+                self.trim();
+            }
+        }
+
+        impl<$($g)*> ShrAssign<usize> for $t {
+            fn shr_assign(&mut self, k: usize) {
+                shr_words(&mut self.b, k);
+            }
+        }
+
+        impl_bitset! { @binop [$($g)*] $t, BitAnd, bitand, bitand_assign }
+        impl_bitset! { @binop [$($g)*] $t, BitOr, bitor, bitor_assign }
+        impl_bitset! { @binop [$($g)*] $t, BitXor, bitxor, bitxor_assign }
+        impl_bitset! { @shift [$($g)*] $t, Shl, shl, shl_assign }
+        impl_bitset! { @shift [$($g)*] $t, Shr, shr, shr_assign }
+
+        impl<$($g)*> Not for $t {
+            type Output = $t;
+            fn not(mut self) -> $t {
+                for a in self.b.iter_mut() { *a = !*a; }
+                self.trim();
+                self
+            }
+        }
+
+        impl<$($g)*> Not for &$t {
+            type Output = $t;
+            fn not(self) -> $t { !self.clone() }
+        }
+
+        impl<'a, $($g)*> IntoIterator for &'a $t {
+            type Item = usize;
+            type IntoIter = Ones<'a>;
+            fn into_iter(self) -> Ones<'a> { self.iter() }
+        }
+    };
+    (@binop [$($g:tt)*] $t:ty, $tr:ident, $f:ident, $fa:ident) => {
+        impl<$($g)*> $tr<&$t> for $t {
+            type Output = $t;
+            fn $f(mut self, rhs: &$t) -> $t {
+                self.$fa(rhs);
+                self
+            }
+        }
+
+        impl<$($g)*> $tr<&$t> for &$t {
+            type Output = $t;
+            fn $f(self, rhs: &$t) -> $t { self.clone().$f(rhs) }
+        }
+    };
+    (@shift [$($g:tt)*] $t:ty, $tr:ident, $f:ident, $fa:ident) => {
+        impl<$($g)*> $tr<usize> for $t {
+            type Output = $t;
+            fn $f(mut self, k: usize) -> $t {
+                self.$fa(k);
+                self
+            }
+        }
+
+        impl<$($g)*> $tr<usize> for &$t {
+            type Output = $t;
+            fn $f(self, k: usize) -> $t { self.clone().$f(k) }
+        }
+    };
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Bitset {
     b: Vec<u64>,
     n: usize,
@@ -5,20 +198,106 @@ pub struct Bitset {
 
 impl Bitset {
     pub fn new(n: usize) -> Self {
-        let s = (n + 63) / 64;
-        Self { b: std::iter::repeat(0).take(s).collect(), n }
+        Self { b: vec![0; n.div_ceil(64)], n }
     }
 
-    pub fn set(&mut self, i: usize, v: bool) {
-        assert!(i < self.n);
-        match v {
-            true => self.b[i / 64] |= 1u64 << (i & 63),
-            false => self.b[i / 64] &= !(1u64 << (i & 63)),
+    pub fn len(&self) -> usize { self.n }
+
+    // new bits are 0
+    pub fn resize(&mut self, n: usize) {
+        self.b.resize(n.div_ceil(64), 0);
+        self.n = n;
+        self.trim();
+    }
+}
+
+impl_bitset! { [] Bitset }
+
+// bitset of `64 * W` bits stored inline
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct FixedBitset<const W: usize> {
+    b: [u64; W],
+}
+
+impl<const W: usize> FixedBitset<W> {
+    pub fn new() -> Self {
+        Self { b: [0; W] }
+    }
+
+    pub fn len(&self) -> usize { W << 6 }
+}
+
+impl<const W: usize> Default for FixedBitset<W> {
+    fn default() -> Self { Self::new() }
+}
+
+impl_bitset! { [const W: usize] FixedBitset<W> }
+
+#[cfg(test)]
+mod bitset_test {
+    use crate::data_structures::set::bitset::{ Bitset, FixedBitset };
+    use crate::random::{ Random, Xorshift128 };
+
+    fn from_vec(v: &[bool]) -> Bitset {
+        let mut b = Bitset::new(v.len());
+        for (i, &x) in v.iter().enumerate() { b.set(i, x); }
+        b
+    }
+
+    fn to_vec(b: &Bitset) -> Vec<bool> {
+        (0..b.len()).map(|i| b.get(i)).collect()
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(3);
+        for n in [0, 1, 63, 64, 65, 130, 300] {
+            for _ in 0..20 {
+                let x = (0..n).map(|_| rng.rand_u64() & 1 == 0).collect::<Vec<_>>();
+                let y = (0..n).map(|_| rng.rand_u64() & 3 == 0).collect::<Vec<_>>();
+                let (a, b) = (from_vec(&x), from_vec(&y));
+                assert_eq!(to_vec(&(&a & &b)), (0..n).map(|i| x[i] & y[i]).collect::<Vec<_>>());
+                assert_eq!(to_vec(&(&a | &b)), (0..n).map(|i| x[i] | y[i]).collect::<Vec<_>>());
+                assert_eq!(to_vec(&(&a ^ &b)), (0..n).map(|i| x[i] ^ y[i]).collect::<Vec<_>>());
+                assert_eq!(to_vec(&!&a), x.iter().map(|&v| !v).collect::<Vec<_>>());
+                assert_eq!((!&a).count_ones(), n - a.count_ones());
+                let k = (rng.rand_u64() % (n as u64 + 70)) as usize;
+                assert_eq!(to_vec(&(&a << k)), (0..n).map(|i| i >= k && x[i - k]).collect::<Vec<_>>());
+                assert_eq!(to_vec(&(&a >> k)), (0..n).map(|i| i + k < n && x[i + k]).collect::<Vec<_>>());
+                let ones = (0..n).filter(|&i| x[i]).collect::<Vec<_>>();
+                assert_eq!(a.count_ones(), ones.len());
+                assert_eq!(a.iter().collect::<Vec<_>>(), ones);
+                assert_eq!(a.find_first(), ones.first().cloned());
+                for i in 0..n {
+                    assert_eq!(a.find_next(i), ones.iter().find(|&&j| j > i).cloned());
+                }
+                let m = (rng.rand_u64() % 200) as usize;
+                let mut c = a.clone();
+                c.resize(m);
+                assert_eq!(to_vec(&c), (0..m).map(|i| i < n && x[i]).collect::<Vec<_>>());
+                c.resize(n);
+                assert_eq!(to_vec(&c), (0..n).map(|i| i < m && x[i]).collect::<Vec<_>>());
+            }
         }
     }
 
-    pub fn get(&self, i: usize) -> bool {
-        assert!(i < self.n);
-        (self.b[i / 64] & (1u64 << (i & 63))) > 0
+    #[test]
+    fn subset_sum_test() {
+        let items = [3, 5, 7, 11];
+        let mut dp = Bitset::new(30);
+        let mut fdp = FixedBitset::<1>::new();
+        dp.set(0, true);
+        fdp.set(0, true);
+        for &a in &items {
+            dp |= &(&dp << a);
+            fdp |= &(fdp << a);
+        }
+        let expect = vec![0, 3, 5, 7, 8, 10, 11, 12, 14, 15, 16, 18, 19, 21, 23, 26];
+        assert_eq!(dp.iter().collect::<Vec<_>>(), expect);
+        assert_eq!(fdp.iter().collect::<Vec<_>>(), expect);
+        assert_eq!(fdp.len(), 64);
+        assert_eq!((!fdp).count_ones(), 64 - expect.len());
+        assert_eq!((fdp >> 20).find_first(), Some(1));
+        assert!(!(fdp & &!fdp).any());
     }
 }
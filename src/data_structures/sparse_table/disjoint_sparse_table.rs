@@ -0,0 +1,70 @@
+use crate::algebra::*;
+
+use std::ops::Range;
+
+pub struct DisjointSparseTable<T: Monoid> {
+    table: Vec<Vec<T>>,
+}
+
+impl<T: Monoid> DisjointSparseTable<T> {
+    pub fn init(arr: &[T]) -> Self {
+        let sz = std::cmp::max(arr.len(), 2).next_power_of_two();
+        let lg = sz.trailing_zeros() as usize;
+        let mut a = arr.to_vec();
+        a.resize(sz, T::identity());
+        let mut table = vec![a.clone()];
+        for k in 1..lg {
+            let half = 1 << k;
+            let mut t = a.clone();
+            for s in (0..sz).step_by(half << 1) {
+                let mid = s + half;
+                for i in (s..mid - 1).rev() { t[i] = a[i].op(&t[i + 1]); }
+                for i in mid + 1..s + (half << 1) { t[i] = t[i - 1].op(&a[i]); }
+            }
+            table.push(t);
+        }
+        DisjointSparseTable { table }
+    }
+
+    pub fn fold(&self, ran: Range<usize>) -> T {
+        if ran.start >= ran.end { return T::identity() }
+        let (l, r) = (ran.start, ran.end - 1);
+        if l == r { return self.table[0][l].clone() }
+        let k = (usize::BITS - 1 - (l ^ r).leading_zeros()) as usize;
+        self.table[k][l].op(&self.table[k][r])
+    }
+}
+
+#[cfg(test)]
+mod disjoint_sparse_table_test {
+    use crate::algebra::*;
+    use crate::data_structures::sparse_table::disjoint_sparse_table::DisjointSparseTable;
+
+    #[derive(Clone)]
+    struct Affine(u64, u64);
+
+    const M: u64 = 1_000_000_007;
+
+    impl Magma for Affine {
+        fn op(&self, right: &Self) -> Self { Affine(self.0 * right.0 % M, (self.1 * right.0 + right.1) % M) }
+    }
+    impl Associative for Affine {}
+    impl Unital for Affine {
+        fn identity() -> Self { Affine(1, 0) }
+    }
+
+    #[test]
+    fn affine_test() {
+        let arr = (0..13).map(|i| Affine(i * 7 + 2, i * i + 1)).collect::<Vec<_>>();
+        let dst = DisjointSparseTable::init(&arr);
+        for l in 0..arr.len() + 1 {
+            for r in l..arr.len() + 1 {
+                let expected = arr[l..r].iter().fold(Affine::identity(), |s, x| s.op(x));
+                let res = dst.fold(l..r);
+                assert_eq!((res.0, res.1), (expected.0, expected.1));
+            }
+        }
+        let single = DisjointSparseTable::init(&[Affine(3, 4)]);
+        assert_eq!(single.fold(0..1).1, 4);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::algebra::*;
+
+use std::ops::Range;
+
+pub struct SparseTable<T: Monoid + Idempotent> {
+    table: Vec<Vec<T>>,
+}
+
+impl<T: Monoid + Idempotent> SparseTable<T> {
+    pub fn init(arr: &[T]) -> Self {
+        let mut table = vec![arr.to_vec()];
+        let mut k = 1;
+        while (1 << k) <= arr.len() {
+            let prev = &table[k - 1];
+            let next = (0..arr.len() + 1 - (1 << k))
+                .map(|i| prev[i].op(&prev[i + (1 << (k - 1))]))
+                .collect();
+            table.push(next);
+            k += 1;
+        }
+        SparseTable { table }
+    }
+
+    pub fn fold(&self, ran: Range<usize>) -> T {
+        if ran.start >= ran.end { return T::identity() }
+        let k = (usize::BITS - 1 - (ran.end - ran.start).leading_zeros()) as usize;
+        self.table[k][ran.start].op(&self.table[k][ran.end - (1 << k)])
+    }
+}
+
+#[cfg(test)]
+mod sparse_table_test {
+    use crate::algebra::*;
+    use crate::data_structures::sparse_table::sparse_table::SparseTable;
+
+    #[derive(Clone)]
+    struct Mm(usize);
+
+    impl Magma for Mm {
+        fn op(&self, right: &Self) -> Self { Mm(std::cmp::min(self.0, right.0)) }
+    }
+    impl Associative for Mm {}
+    impl Idempotent for Mm {}
+    impl Unital for Mm {
+        fn identity() -> Self { Mm(usize::MAX) }
+    }
+
+    #[test]
+    fn rmq_test() {
+        let arr = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        let st = SparseTable::init(&arr.iter().map(|&x| Mm(x)).collect::<Vec<_>>());
+        for l in 0..arr.len() + 1 {
+            for r in l..arr.len() + 1 {
+                assert_eq!(st.fold(l..r).0, arr[l..r].iter().cloned().min().unwrap_or(usize::MAX));
+            }
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod splay_tree_array;
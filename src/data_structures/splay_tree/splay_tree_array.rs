@@ -0,0 +1,203 @@
+use crate::data_structures::node_traits::*;
+use crate::algebra::*;
+
+use crate::data_structures::node_seq::build;
+pub use crate::data_structures::node_seq::Iter;
+
+pub trait SplayNode: Node {}
+impl<N: Node> SplayNode for N {}
+
+// top-down splay. returns the new root, which is the node found by `key` or the last node
+// visited, and the direction the search fell off from it (None if found).
+fn splay<K, N: SplayNode + KeySearch<K>>(x: Box<N>, key: K) -> (Box<N>, Option<usize>) {
+    splay_by(x, key, |x, key| x.key_search(key))
+}
+
+fn splay_rightmost<N: SplayNode>(x: Box<N>) -> Box<N> {
+    splay_by(x, (), |_, key| Some((1, key))).0
+}
+
+fn splay_by<K, N: SplayNode, F: Fn(&N, K) -> Option<(usize, K)>>(mut x: Box<N>, key: K, search: F) -> (Box<N>, Option<usize>) {
+    // stack[dir] keeps the nodes on the `dir` side, each waiting for its `dir ^ 1` child
+    let mut stack: [Vec<Box<N>>; 2] = [Vec::new(), Vec::new()];
+    x.push();
+    let mut found = search(&x, key);
+    let res = loop {
+        let (dir, key) = match found {
+            Some(s) => s,
+            None => break None,
+        };
+        let mut c = match x.replace(dir, Link::None) {
+            Link::Some(c) => c,
+            _ => break Some(dir),
+        };
+        c.push();
+        match search(&c, key) {
+            Some((d, key)) if d == dir && c.child(d).as_ref().is_some() => {
+                // zig-zig: rotate `c` over `x`, then link `c`
+                x.replace(dir, c.replace(dir ^ 1, Link::None));
+                x.fix();
+                c.replace(dir ^ 1, Link::Some(x));
+                x = c.replace(dir, Link::None).unwrap();
+                stack[dir ^ 1].push(c);
+                x.push();
+                found = search(&x, key);
+            }
+            Some((d, key)) if c.child(d).as_ref().is_some() => {
+                // zig-zag: link `x` and `c`
+                stack[dir ^ 1].push(x);
+                x = c.replace(d, Link::None).unwrap();
+                stack[d ^ 1].push(c);
+                x.push();
+                found = search(&x, key);
+            }
+            s => {
+                stack[dir ^ 1].push(x);
+                x = c;
+                found = s;
+            }
+        }
+    };
+    for (dir, st) in stack.iter_mut().enumerate() {
+        let mut ch = x.replace(dir, Link::None);
+        while let Some(mut p) = st.pop() {
+            p.replace(dir ^ 1, ch);
+            p.fix();
+            ch = Link::Some(p);
+        }
+        x.replace(dir, ch);
+    }
+    x.fix();
+    (x, res)
+}
+
+pub struct SplayTree<N: SplayNode> {
+    root: Link<N>,
+}
+
+impl<N: SplayNode> SplayTree<N> {
+    pub fn empty() -> Self {
+        SplayTree { root: Link::None }
+    }
+    pub fn new(mut node: N) -> Self {
+        node.fix();
+        SplayTree { root: Link::Some(Box::new(node)) }
+    }
+    pub fn merge(mut self, mut right: Self) -> Self {
+        match self.root.take() {
+            Link::Some(l) => {
+                let mut root = splay_rightmost(l);
+                root.replace(1, right.root.take());
+                root.fix();
+                SplayTree { root: Link::Some(root) }
+            }
+            _ => right,
+        }
+    }
+    // the node found by `pos` goes to the right
+    pub fn split<K>(mut self, pos: K) -> (Self, Self)
+    where N: KeySearch<K> {
+        match self.root.take() {
+            Link::Some(root) => {
+                let (mut root, res) = splay(root, pos);
+                if res == Some(1) {
+                    let r = root.replace(1, Link::None);
+                    root.fix();
+                    (SplayTree { root: Link::Some(root) }, SplayTree { root: r })
+                }
+                else {
+                    let l = root.replace(0, Link::None);
+                    root.fix();
+                    (SplayTree { root: l }, SplayTree { root: Link::Some(root) })
+                }
+            }
+            _ => (Self::empty(), Self::empty()),
+        }
+    }
+    pub fn at<K>(&mut self, pos: K) -> Option<&N::Value>
+    where N: KeySearch<K> {
+        let root = match self.root.take() {
+            Link::Some(root) => root,
+            _ => return None,
+        };
+        let (root, res) = splay(root, pos);
+        self.root = Link::Some(root);
+        match res {
+            None => self.root.as_ref().map(|r| r.value()),
+            _ => None,
+        }
+    }
+    pub fn set<K>(&mut self, pos: K, val: N::Value)
+    where N: KeySearch<K> {
+        let root = match self.root.take() {
+            Link::Some(root) => root,
+            _ => panic!("set on an empty tree"),
+        };
+        let (mut root, res) = splay(root, pos);
+        assert!(res.is_none());
+        *root.value_mut() = val;
+        root.fix();
+        self.root = Link::Some(root);
+    }
+    pub fn root(&self) -> Option<&N> {
+        self.root.as_ref().map(|r| &**r)
+    }
+    pub fn into_root(mut self) -> Option<Box<N>> {
+        match self.root.take() {
+            Link::Some(r) => Some(r),
+            _ => None,
+        }
+    }
+    pub fn from_vec(vec: Vec<N::Value>) -> Self where N: ValueNode {
+        let n = vec.len();
+        SplayTree { root: build(&mut vec.into_iter(), n) }
+    }
+}
+
+// sequential access leaves a path as deep as the tree is large
+impl<N: SplayNode> Drop for SplayTree<N> {
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        if let Link::Some(x) = self.root.take() { stack.push(x); }
+        while let Some(mut x) = stack.pop() {
+            for dir in 0..2 {
+                if let Link::Some(c) = x.replace(dir, Link::None) { stack.push(c); }
+            }
+        }
+    }
+}
+
+impl_sequence! { SplayTree, SplayNode }
+
+#[cfg(test)]
+mod splay_map_test {
+    use crate::data_structures::node_traits::*;
+    use crate::data_structures::splay_tree::splay_tree_array::SplayTree;
+
+    def_node! { NodeTest, key: usize, usize; size, }
+
+    #[test]
+    fn key_test() {
+        let mut t = SplayTree::empty();
+        for i in 0..10 {
+            t = t.merge(SplayTree::new(NodeTest::new(i * 2, i)));
+        }
+        for i in 0..10 {
+            assert_eq!(*t.at(&(i * 2)).unwrap(), i);
+        }
+        assert!(t.at(&7).is_none());
+        t.set(&6, 30);
+        let (mut l, mut r) = t.split(&7);
+        assert_eq!(l.size(), 4);
+        assert_eq!(r.size(), 6);
+        assert_eq!(*l.at(Position(3)).unwrap(), 30);
+        assert_eq!(*r.at(&8).unwrap(), 4);
+    }
+}
+
+#[cfg(test)]
+mod splay_sequence_test {
+    use crate::data_structures::splay_tree::splay_tree_array::SplayTree;
+
+    sequence_test! { SplayTree; }
+}
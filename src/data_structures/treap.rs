@@ -0,0 +1 @@
+pub mod treap_array;
@@ -0,0 +1,181 @@
+use crate::data_structures::node_traits::*;
+use crate::algebra::*;
+use crate::random::{ Random, Xorshift128 };
+
+use std::cell::RefCell;
+
+use crate::data_structures::node_seq::at;
+pub use crate::data_structures::node_seq::Iter;
+
+pub trait TreapNode: Node + PriorityNode {}
+impl<N: Node + PriorityNode> TreapNode for N {}
+
+thread_local! {
+    static PRIORITY: RefCell<Xorshift128> = RefCell::new(Xorshift128::new(88172645463325252));
+}
+
+fn gen_priority() -> u64 {
+    PRIORITY.with(|rng| rng.borrow_mut().rand_u64())
+}
+
+fn merge<N: TreapNode>(l: Link<N>, r: Link<N>) -> Link<N> {
+    match (l, r) {
+        (Link::Some(mut l), Link::Some(mut r)) => {
+            if l.priority() > r.priority() {
+                l.push();
+                let lr = l.replace(1, Link::None);
+                l.replace(1, merge(lr, Link::Some(r)));
+                l.fix();
+                Link::Some(l)
+            }
+            else {
+                r.push();
+                let rl = r.replace(0, Link::None);
+                r.replace(0, merge(Link::Some(l), rl));
+                r.fix();
+                Link::Some(r)
+            }
+        }
+        (Link::Some(l), _) => Link::Some(l),
+        (_, r) => r,
+    }
+}
+
+// the node found by `pos` goes to the right
+fn split<K, N: TreapNode + KeySearch<K>>(x: Link<N>, pos: K) -> (Link<N>, Link<N>) {
+    let mut x = match x {
+        Link::Some(x) => x,
+        _ => return (Link::None, Link::None),
+    };
+    x.push();
+    match x.key_search(pos) {
+        Some((0, np)) => {
+            let (l, r) = split(x.replace(0, Link::None), np);
+            x.replace(0, r);
+            x.fix();
+            (l, Link::Some(x))
+        }
+        Some((_, np)) => {
+            let (l, r) = split(x.replace(1, Link::None), np);
+            x.replace(1, l);
+            x.fix();
+            (Link::Some(x), r)
+        }
+        None => {
+            let l = x.replace(0, Link::None);
+            x.fix();
+            (l, Link::Some(x))
+        }
+    }
+}
+
+fn set<K, N: TreapNode + KeySearch<K>>(x: &mut Box<N>, pos: K, val: N::Value) {
+    x.push();
+    match x.key_search(pos) {
+        Some((dir, np)) => set(x.child_mut(dir).as_mut().unwrap(), np, val),
+        None => *x.value_mut() = val,
+    }
+    x.fix();
+}
+
+// cartesian tree of the values with random priorities, built in linear time
+fn build<N: TreapNode + ValueNode>(vec: Vec<N::Value>) -> Link<N> {
+    let mut stack: Vec<Box<N>> = Vec::new();
+    for val in vec {
+        let mut x = Box::new(N::from_value(val));
+        x.set_priority(gen_priority());
+        let mut last = Link::None;
+        while stack.last().is_some_and(|t| t.priority() < x.priority()) {
+            let mut t = stack.pop().unwrap();
+            t.replace(1, last);
+            t.fix();
+            last = Link::Some(t);
+        }
+        x.replace(0, last);
+        stack.push(x);
+    }
+    let mut last = Link::None;
+    while let Some(mut t) = stack.pop() {
+        t.replace(1, last);
+        t.fix();
+        last = Link::Some(t);
+    }
+    last
+}
+
+pub struct Treap<N: TreapNode> {
+    root: Link<N>,
+}
+
+impl<N: TreapNode> Treap<N> {
+    pub fn empty() -> Self {
+        Treap { root: Link::None }
+    }
+    pub fn new(mut node: N) -> Self {
+        node.set_priority(gen_priority());
+        node.fix();
+        Treap { root: Link::Some(Box::new(node)) }
+    }
+    pub fn merge(self, right: Self) -> Self {
+        Treap { root: merge(self.root, right.root) }
+    }
+    pub fn split<K>(self, pos: K) -> (Self, Self)
+    where N: KeySearch<K> {
+        let (l, r) = split(self.root, pos);
+        (Treap { root: l }, Treap { root: r })
+    }
+    pub fn at<K>(&mut self, pos: K) -> Option<&N::Value>
+    where N: KeySearch<K> {
+        at(self.root.as_mut()?, pos)
+    }
+    pub fn set<K>(&mut self, pos: K, val: N::Value)
+    where N: KeySearch<K> {
+        set(self.root.as_mut().expect("set on an empty tree"), pos, val)
+    }
+    pub fn root(&self) -> Option<&N> {
+        self.root.as_ref().map(|r| &**r)
+    }
+    pub fn into_root(self) -> Option<Box<N>> {
+        match self.root {
+            Link::Some(r) => Some(r),
+            _ => None,
+        }
+    }
+    pub fn from_vec(vec: Vec<N::Value>) -> Self where N: ValueNode {
+        Treap { root: build(vec) }
+    }
+}
+
+impl_sequence! { Treap, TreapNode }
+
+#[cfg(test)]
+mod treap_map_test {
+    use crate::data_structures::node_traits::*;
+    use crate::data_structures::treap::treap_array::Treap;
+
+    def_node! { NodeTest, key: usize, usize; size, priority, }
+
+    #[test]
+    fn key_test() {
+        let mut t = Treap::empty();
+        for i in 0..10 {
+            t = t.merge(Treap::new(NodeTest::new(i * 2, i)));
+        }
+        for i in 0..10 {
+            assert_eq!(*t.at(&(i * 2)).unwrap(), i);
+        }
+        t.set(&6, 30);
+        let (mut l, mut r) = t.split(&7);
+        assert_eq!(l.size(), 4);
+        assert_eq!(r.size(), 6);
+        assert_eq!(*l.at(Position(3)).unwrap(), 30);
+        assert_eq!(*r.at(&8).unwrap(), 4);
+    }
+}
+
+#[cfg(test)]
+mod treap_sequence_test {
+    use crate::data_structures::treap::treap_array::Treap;
+
+    sequence_test! { Treap; priority }
+}
@@ -1,2 +1,5 @@
 pub mod directed_tree;
 pub mod heavy_light_decomposition;
+pub mod link_cut_tree;
+pub mod euler_tour_tree;
+mod splay_arena;
@@ -0,0 +1,279 @@
+use crate::algebra::*;
+use crate::data_structures::tree::splay_arena::{ Node, SplayArena, NIL };
+
+use std::collections::HashMap;
+
+// dynamic forest kept as Euler tours on splay trees.
+// the tour has one node per vertex and one per directed edge, and is treated as a cycle,
+// so subtrees are given by the vertex together with its parent.
+// nodes below `n` are vertices, the others are arcs whose values are the identity.
+pub struct EulerTourTree<T: Monoid + Effect<E>, E: Monoid> {
+    n: usize,
+    node: Vec<Node<T, E>>,
+    arc: HashMap<(usize, usize), usize>,
+    free: Vec<usize>,
+}
+
+impl<T: Monoid + Effect<E>, E: Monoid> EulerTourTree<T, E> {
+    pub fn new(vals: Vec<T>) -> Self {
+        EulerTourTree {
+            n: vals.len(),
+            node: vals.into_iter().map(Node::new).collect(),
+            arc: HashMap::new(),
+            free: Vec::new(),
+        }
+    }
+
+    fn new_arc(&mut self, u: usize, v: usize) -> usize {
+        let n = Node::new(T::identity());
+        let x = match self.free.pop() {
+            Some(x) => { self.node[x] = n; x }
+            None => { self.node.push(n); self.node.len() - 1 }
+        };
+        self.arc.insert((u, v), x);
+        x
+    }
+
+    // splits the tour into the part before `x` and the part starting at `x`
+    fn split_before(&mut self, x: usize) -> (usize, usize) {
+        self.splay(x);
+        let l = self.node[x].ch[0];
+        if l != NIL {
+            self.node[l].par = NIL;
+            self.node[x].ch[0] = NIL;
+            self.fix(x);
+        }
+        (l, x)
+    }
+
+    // splits the tour into the part ending at `x` and the part after `x`
+    fn split_after(&mut self, x: usize) -> (usize, usize) {
+        self.splay(x);
+        let r = self.node[x].ch[1];
+        if r != NIL {
+            self.node[r].par = NIL;
+            self.node[x].ch[1] = NIL;
+            self.fix(x);
+        }
+        (x, r)
+    }
+
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        if a == NIL { return b }
+        if b == NIL { return a }
+        let mut x = a;
+        loop {
+            self.push(x);
+            match self.node[x].ch[1] {
+                NIL => break,
+                r => x = r,
+            }
+        }
+        self.splay(x);
+        self.node[x].ch[1] = b;
+        self.node[b].par = x;
+        self.fix(x);
+        x
+    }
+
+    /// rotates the tour of the tree of `v` so that it starts at `v`
+    pub fn reroot(&mut self, v: usize) {
+        let (l, r) = self.split_before(v);
+        self.merge(r, l);
+    }
+
+    pub fn connected(&mut self, u: usize, v: usize) -> bool {
+        u == v || self.leftmost(u) == self.leftmost(v)
+    }
+
+    /// adds the edge `u`-`v`. returns false if they are already connected
+    pub fn link(&mut self, u: usize, v: usize) -> bool {
+        if self.connected(u, v) { return false }
+        self.reroot(u);
+        self.splay(u);
+        let uv = self.new_arc(u, v);
+        let vu = self.new_arc(v, u);
+        let (a, b) = self.split_after(v);
+        let a = self.merge(a, vu);
+        let a = self.merge(a, u);
+        let a = self.merge(a, uv);
+        self.merge(a, b);
+        true
+    }
+
+    /// removes the edge `u`-`v`. returns false if there is no such edge
+    pub fn cut(&mut self, u: usize, v: usize) -> bool {
+        let (uv, vu) = match (self.arc.remove(&(u, v)), self.arc.remove(&(v, u))) {
+            (Some(uv), Some(vu)) => (uv, vu),
+            _ => return false,
+        };
+        self.reroot(u);
+        let (l, _) = self.split_before(uv);
+        let (_, r) = self.split_after(vu);
+        self.split_after(uv);
+        self.split_before(vu);
+        self.merge(l, r);
+        self.free.push(uv);
+        self.free.push(vu);
+        true
+    }
+
+    // applies `f` to the splay tree of the vertices in the subtree of `v` whose parent is `p`,
+    // or returns None if there is no edge `v`-`p`
+    fn with_subtree<R, F: FnOnce(&mut Self, usize) -> R>(&mut self, v: usize, p: usize, f: F) -> Option<R> {
+        let pv = *self.arc.get(&(p, v))?;
+        let vp = self.arc[&(v, p)];
+        self.reroot(p);
+        let (l, _) = self.split_after(pv);
+        let (m, r) = self.split_before(vp);
+        let res = f(self, m);
+        let m = self.merge(m, r);
+        self.merge(l, m);
+        Some(res)
+    }
+
+    /// fold of the values in the subtree of `v` when `p` is its parent.
+    /// returns None if `p` is not adjacent to `v`
+    pub fn subtree_fold(&mut self, v: usize, p: usize) -> Option<T> {
+        self.with_subtree(v, p, |t, m| t.fold_of(m))
+    }
+
+    /// applies `e` to the values in the subtree of `v` when `p` is its parent.
+    /// returns false if `p` is not adjacent to `v`
+    pub fn subtree_update(&mut self, v: usize, p: usize, e: E) -> bool {
+        self.with_subtree(v, p, |t, m| t.effect(m, &e)).is_some()
+    }
+
+    /// fold of the values in the tree containing `v`
+    pub fn tree_fold(&mut self, v: usize) -> T {
+        self.splay(v);
+        self.node[v].fold.clone()
+    }
+
+    pub fn tree_update(&mut self, v: usize, e: E) {
+        self.splay(v);
+        self.effect(v, &e);
+    }
+
+    /// number of vertices in the tree containing `v`
+    pub fn tree_size(&mut self, v: usize) -> usize {
+        self.splay(v);
+        self.node[v].size.div_ceil(3)
+    }
+
+    pub fn get(&mut self, v: usize) -> T {
+        self.splay(v);
+        self.node[v].val.clone()
+    }
+
+    pub fn set(&mut self, v: usize, val: T) {
+        self.splay(v);
+        self.node[v].val = val;
+        self.fix(v);
+    }
+}
+
+impl<T: Monoid + Effect<E>, E: Monoid> SplayArena for EulerTourTree<T, E> {
+    type T = T;
+    type E = E;
+
+    fn node(&self, x: usize) -> &Node<T, E> { &self.node[x] }
+    fn node_mut(&mut self, x: usize) -> &mut Node<T, E> { &mut self.node[x] }
+
+    fn acts_on(&self, x: usize) -> bool { x < self.n }
+}
+
+#[cfg(test)]
+mod euler_tour_tree_test {
+    use crate::random::{ Random, Xorshift128 };
+    use crate::data_structures::tree::euler_tour_tree::EulerTourTree;
+
+    monoid! { #[derive(Debug, PartialEq, Eq)] Sm, (u64, u64), (0, 0), |a, b| (a.0 + b.0, a.1 + b.1) }
+    monoid! { Aq, u64, 0, |a, b| a + b }
+    action! { Aq => Sm, |x, f| (x.0 + f * x.1, x.1) }
+
+    // vertices reachable from `v` without using the edge `v`-`p`
+    fn component(adj: &[Vec<usize>], v: usize, p: usize) -> Vec<usize> {
+        let mut seen = vec![false; adj.len()];
+        let mut stack = vec![v];
+        seen[v] = true;
+        let mut res = Vec::new();
+        while let Some(x) = stack.pop() {
+            res.push(x);
+            for &y in adj[x].iter() {
+                if !seen[y] && (x != v || y != p) {
+                    seen[y] = true;
+                    stack.push(y);
+                }
+            }
+        }
+        res
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(3);
+        let n = 30;
+        let mut val: Vec<u64> = (0..n).map(|_| rng.rand_u64() % 1000).collect();
+        let mut ett = EulerTourTree::<Sm, Aq>::new(val.iter().map(|&x| Sm((x, 1))).collect());
+        let mut adj = vec![Vec::new(); n];
+        for _ in 0..5000 {
+            let u = (rng.rand_u64() % n as u64) as usize;
+            let v = (rng.rand_u64() % n as u64) as usize;
+            match rng.rand_u64() % 8 {
+                0 | 1 => {
+                    let ok = !component(&adj, u, n).contains(&v);
+                    assert_eq!(ett.link(u, v), ok);
+                    if ok {
+                        adj[u].push(v);
+                        adj[v].push(u);
+                    }
+                }
+                2 => {
+                    let u = if adj[v].is_empty() { u } else { adj[v][(rng.rand_u64() % adj[v].len() as u64) as usize] };
+                    let ok = adj[v].contains(&u);
+                    assert_eq!(ett.cut(u, v), ok);
+                    if ok {
+                        adj[u].retain(|&x| x != v);
+                        adj[v].retain(|&x| x != u);
+                    }
+                }
+                3 => {
+                    assert_eq!(ett.connected(u, v), component(&adj, u, n).contains(&v));
+                    if !adj[v].contains(&u) {
+                        assert!(ett.subtree_fold(v, u).is_none());
+                        assert!(!ett.subtree_update(v, u, Aq(1)));
+                    }
+                    ett.reroot(v);
+                }
+                4 if !adj[v].is_empty() => {
+                    let p = adj[v][(rng.rand_u64() % adj[v].len() as u64) as usize];
+                    let c = component(&adj, v, p);
+                    let s = ett.subtree_fold(v, p).unwrap();
+                    assert_eq!(s.0.0, c.iter().map(|&x| val[x]).sum::<u64>());
+                    assert_eq!(s.0.1, c.len() as u64);
+                }
+                5 if !adj[v].is_empty() => {
+                    let p = adj[v][(rng.rand_u64() % adj[v].len() as u64) as usize];
+                    let x = rng.rand_u64() % 100;
+                    assert!(ett.subtree_update(v, p, Aq(x)));
+                    for w in component(&adj, v, p) { val[w] += x; }
+                }
+                6 => {
+                    let c = component(&adj, v, n);
+                    assert_eq!(ett.tree_fold(v).0.0, c.iter().map(|&x| val[x]).sum::<u64>());
+                    assert_eq!(ett.tree_size(v), c.len());
+                    let x = rng.rand_u64() % 100;
+                    ett.tree_update(u, Aq(x));
+                    for w in component(&adj, u, n) { val[w] += x; }
+                }
+                _ => {
+                    assert_eq!(ett.get(v).0.0, val[v]);
+                    let x = rng.rand_u64() % 1000;
+                    ett.set(u, Sm((x, 1)));
+                    val[u] = x;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use crate::algebra::*;
+use crate::data_structures::tree::splay_arena::{ Node, SplayArena, NIL };
+
+// dynamic forest with path folds and path actions.
+// `T::reverse` must return the fold of the reversed sequence.
+// without path actions, use `FoldLinkCutTree`.
+pub struct LinkCutTree<T: Monoid + Reverse + Effect<E>, E: Monoid> {
+    node: Vec<Node<T, E>>,
+}
+
+impl<T: Monoid + Reverse + Effect<E>, E: Monoid> LinkCutTree<T, E> {
+    pub fn new(vals: Vec<T>) -> Self {
+        LinkCutTree {
+            node: vals.into_iter().map(Node::new).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize { self.node.len() }
+
+    pub fn is_empty(&self) -> bool { self.node.is_empty() }
+
+    fn reverse(&mut self, x: usize) {
+        if x == NIL { return }
+        let n = &mut self.node[x];
+        n.ch.swap(0, 1);
+        n.fold = n.fold.reverse();
+        n.rev ^= true;
+    }
+
+    // makes the path from the root to `x` preferred; returns the last path-parent jumped from
+    fn access(&mut self, x: usize) -> usize {
+        let mut last = NIL;
+        let mut y = x;
+        while y != NIL {
+            self.splay(y);
+            self.node[y].ch[1] = last;
+            self.fix(y);
+            last = y;
+            y = self.node[y].par;
+        }
+        self.splay(x);
+        last
+    }
+
+    /// makes `v` the root of its tree
+    pub fn evert(&mut self, v: usize) {
+        self.access(v);
+        self.reverse(v);
+        self.push(v);
+    }
+
+    pub fn root(&mut self, v: usize) -> usize {
+        self.access(v);
+        self.leftmost(v)
+    }
+
+    pub fn connected(&mut self, u: usize, v: usize) -> bool {
+        u == v || self.root(u) == self.root(v)
+    }
+
+    /// adds the edge `u`-`v`. returns false if they are already connected
+    pub fn link(&mut self, u: usize, v: usize) -> bool {
+        if self.connected(u, v) { return false }
+        self.evert(u);
+        self.node[u].par = v;
+        true
+    }
+
+    /// removes the edge `u`-`v`. returns false if there is no such edge.
+    /// if `u` and `v` are connected, `u` becomes the root of its tree and `v` of the split-off one
+    pub fn cut(&mut self, u: usize, v: usize) -> bool {
+        if u == v || !self.connected(u, v) { return false }
+        self.evert(u);
+        self.access(v);
+        if self.node[v].ch[0] != u || self.node[u].ch != [NIL; 2] { return false }
+        self.node[v].ch[0] = NIL;
+        self.node[u].par = NIL;
+        self.fix(v);
+        true
+    }
+
+    /// lowest common ancestor with respect to the current root, or None if disconnected
+    pub fn lca(&mut self, u: usize, v: usize) -> Option<usize> {
+        if !self.connected(u, v) { return None }
+        self.access(u);
+        Some(self.access(v))
+    }
+
+    pub fn get(&mut self, v: usize) -> T {
+        self.access(v);
+        self.node[v].val.clone()
+    }
+
+    pub fn set(&mut self, v: usize, val: T) {
+        self.access(v);
+        self.node[v].val = val;
+        self.fix(v);
+    }
+
+    /// fold of the values on the path from `u` to `v`, in this order. `u` becomes the root
+    pub fn path_fold(&mut self, u: usize, v: usize) -> Option<T> {
+        if !self.connected(u, v) { return None }
+        self.evert(u);
+        self.access(v);
+        Some(self.node[v].fold.clone())
+    }
+
+    /// applies `e` to the values on the path from `u` to `v`. `u` becomes the root
+    pub fn path_update(&mut self, u: usize, v: usize, e: E) -> bool {
+        if !self.connected(u, v) { return false }
+        self.evert(u);
+        self.access(v);
+        self.effect(v, &e);
+        true
+    }
+}
+
+// the splay trees are the preferred paths; a root's `par` is the path parent
+impl<T: Monoid + Reverse + Effect<E>, E: Monoid> SplayArena for LinkCutTree<T, E> {
+    type T = T;
+    type E = E;
+
+    fn node(&self, x: usize) -> &Node<T, E> { &self.node[x] }
+    fn node_mut(&mut self, x: usize) -> &mut Node<T, E> { &mut self.node[x] }
+
+    fn is_root(&self, x: usize) -> bool {
+        let p = self.node[x].par;
+        p == NIL || (self.node[p].ch[0] != x && self.node[p].ch[1] != x)
+    }
+
+    fn push(&mut self, x: usize) {
+        if self.node[x].rev {
+            let [l, r] = self.node[x].ch;
+            self.reverse(l);
+            self.reverse(r);
+            self.node[x].rev = false;
+        }
+        self.push_action(x);
+    }
+}
+
+#[derive(Clone)]
+struct NoAction;
+
+impl Magma for NoAction {
+    fn op(&self, _: &Self) -> Self { NoAction }
+}
+impl Associative for NoAction {}
+impl Unital for NoAction {
+    fn identity() -> Self { NoAction }
+}
+
+#[derive(Clone)]
+struct Folded<T>(T);
+
+impl<T: Magma> Magma for Folded<T> {
+    fn op(&self, rhs: &Self) -> Self { Folded(self.0.op(&rhs.0)) }
+}
+impl<T: Associative> Associative for Folded<T> {}
+impl<T: Unital> Unital for Folded<T> {
+    fn identity() -> Self { Folded(T::identity()) }
+}
+impl<T: Reverse> Reverse for Folded<T> {
+    fn reverse(&self) -> Self { Folded(self.0.reverse()) }
+}
+impl<T: Clone> Effect<NoAction> for Folded<T> {
+    fn effect(&self, _: &NoAction) -> Self { self.clone() }
+}
+
+// link-cut tree with path folds only
+pub struct FoldLinkCutTree<T: Monoid + Reverse> {
+    lct: LinkCutTree<Folded<T>, NoAction>,
+}
+
+impl<T: Monoid + Reverse> FoldLinkCutTree<T> {
+    pub fn new(vals: Vec<T>) -> Self {
+        FoldLinkCutTree { lct: LinkCutTree::new(vals.into_iter().map(Folded).collect()) }
+    }
+    pub fn len(&self) -> usize { self.lct.len() }
+    pub fn is_empty(&self) -> bool { self.lct.is_empty() }
+    pub fn evert(&mut self, v: usize) { self.lct.evert(v) }
+    pub fn root(&mut self, v: usize) -> usize { self.lct.root(v) }
+    pub fn connected(&mut self, u: usize, v: usize) -> bool { self.lct.connected(u, v) }
+    pub fn link(&mut self, u: usize, v: usize) -> bool { self.lct.link(u, v) }
+    pub fn cut(&mut self, u: usize, v: usize) -> bool { self.lct.cut(u, v) }
+    pub fn lca(&mut self, u: usize, v: usize) -> Option<usize> { self.lct.lca(u, v) }
+    pub fn get(&mut self, v: usize) -> T { self.lct.get(v).0 }
+    pub fn set(&mut self, v: usize, val: T) { self.lct.set(v, Folded(val)) }
+    pub fn path_fold(&mut self, u: usize, v: usize) -> Option<T> { self.lct.path_fold(u, v).map(|f| f.0) }
+}
+
+#[cfg(test)]
+mod link_cut_tree_test {
+    use crate::algebra::*;
+    use crate::random::{ Random, Xorshift128 };
+    use crate::data_structures::tree::link_cut_tree::{ LinkCutTree, FoldLinkCutTree };
+
+    const P: u64 = 1_000_000_007;
+    const B: u64 = 10007;
+
+    // polynomial hash of the sequence in both directions
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    struct Hs { fwd: u64, bwd: u64, pw: u64 }
+
+    impl Magma for Hs {
+        fn op(&self, r: &Self) -> Self {
+            Hs {
+                fwd: (self.fwd * r.pw + r.fwd) % P,
+                bwd: (r.bwd * self.pw + self.bwd) % P,
+                pw: self.pw * r.pw % P,
+            }
+        }
+    }
+    impl Associative for Hs {}
+    impl Unital for Hs {
+        fn identity() -> Self { Hs { fwd: 0, bwd: 0, pw: 1 } }
+    }
+    impl Reverse for Hs {
+        fn reverse(&self) -> Self { Hs { fwd: self.bwd, bwd: self.fwd, pw: self.pw } }
+    }
+    fn hs(x: u64) -> Hs { Hs { fwd: x, bwd: x, pw: B } }
+
+    monoid! { #[derive(Debug, PartialEq, Eq)] Sm, (u64, u64), (0, 0), |a, b| (a.0 + b.0, a.1 + b.1) }
+    impl Reverse for Sm {
+        fn reverse(&self) -> Self { self.clone() }
+    }
+    monoid! { Aq, u64, 0, |a, b| a + b }
+    action! { Aq => Sm, |x, f| (x.0 + f * x.1, x.1) }
+
+    // naive forest where each component remembers its root
+    struct Naive {
+        adj: Vec<Vec<usize>>,
+        root: Vec<bool>,
+    }
+
+    impl Naive {
+        fn new(n: usize) -> Self { Naive { adj: vec![Vec::new(); n], root: vec![true; n] } }
+        // parent of each vertex of the component of `v`, rooted at `r`
+        fn bfs(&self, r: usize) -> Vec<Option<usize>> {
+            let mut par = vec![None; self.adj.len()];
+            let mut seen = vec![false; self.adj.len()];
+            let mut que = std::collections::VecDeque::new();
+            seen[r] = true;
+            que.push_back(r);
+            while let Some(x) = que.pop_front() {
+                for &y in self.adj[x].iter() {
+                    if !seen[y] {
+                        seen[y] = true;
+                        par[y] = Some(x);
+                        que.push_back(y);
+                    }
+                }
+            }
+            par
+        }
+        fn component(&self, v: usize) -> Vec<usize> {
+            let par = self.bfs(v);
+            (0..self.adj.len()).filter(|&x| x == v || par[x].is_some()).collect()
+        }
+        fn root_of(&self, v: usize) -> usize {
+            self.component(v).into_iter().find(|&x| self.root[x]).unwrap()
+        }
+        fn evert(&mut self, v: usize) {
+            for x in self.component(v) { self.root[x] = false; }
+            self.root[v] = true;
+        }
+        // vertices on the path from `u` to `v`
+        fn path(&self, u: usize, v: usize) -> Option<Vec<usize>> {
+            let par = self.bfs(v);
+            if u != v && par[u].is_none() { return None }
+            let mut res = vec![u];
+            let mut x = u;
+            while let Some(p) = par[x] {
+                res.push(p);
+                x = p;
+            }
+            Some(res)
+        }
+        fn lca(&self, u: usize, v: usize) -> Option<usize> {
+            let r = self.root_of(u);
+            let pu = self.path(u, r)?;
+            let pv = self.path(v, r)?;
+            pu.into_iter().find(|x| pv.contains(x))
+        }
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(12);
+        let n = 30;
+        let init: Vec<u64> = (0..n).map(|_| rng.rand_u64() % 1000).collect();
+        let mut val = init.clone();
+        let mut lct = FoldLinkCutTree::<Hs>::new(init.iter().map(|&x| hs(x)).collect());
+        let mut sum = LinkCutTree::<Sm, Aq>::new(init.iter().map(|&x| Sm((x, 1))).collect());
+        let mut naive = Naive::new(n);
+        for _ in 0..5000 {
+            let u = (rng.rand_u64() % n as u64) as usize;
+            let v = (rng.rand_u64() % n as u64) as usize;
+            match rng.rand_u64() % 7 {
+                0 | 1 => {
+                    let ok = naive.path(u, v).is_none();
+                    assert_eq!(lct.link(u, v), ok);
+                    assert_eq!(sum.link(u, v), ok);
+                    if ok {
+                        let r = naive.root_of(v);
+                        naive.evert(u);
+                        naive.adj[u].push(v);
+                        naive.adj[v].push(u);
+                        naive.evert(r);
+                    }
+                }
+                2 => {
+                    let u = if naive.adj[v].is_empty() || rng.rand_u64() & 3 == 0 { u }
+                            else { naive.adj[v][(rng.rand_u64() % naive.adj[v].len() as u64) as usize] };
+                    let ok = naive.adj[v].contains(&u);
+                    assert_eq!(lct.cut(u, v), ok);
+                    assert_eq!(sum.cut(u, v), ok);
+                    if naive.path(u, v).is_some() && u != v { naive.evert(u); }
+                    if ok {
+                        naive.adj[u].retain(|&x| x != v);
+                        naive.adj[v].retain(|&x| x != u);
+                        naive.root[v] = true;
+                    }
+                }
+                3 => {
+                    let expected = naive.path(u, v).map(|p| p.iter().fold(Hs::identity(), |acc, &x| acc.op(&hs(val[x]))));
+                    assert_eq!(lct.path_fold(u, v), expected);
+                    if expected.is_some() {
+                        naive.evert(u);
+                        sum.evert(u);
+                    }
+                }
+                4 => {
+                    let x = rng.rand_u64() % 100;
+                    let path = naive.path(u, v);
+                    assert_eq!(sum.path_update(u, v, Aq(x)), path.is_some());
+                    if let Some(p) = path {
+                        naive.evert(u);
+                        lct.evert(u);
+                        for w in p { val[w] += x; }
+                        for (w, &x) in val.iter().enumerate() { lct.set(w, hs(x)); }
+                    }
+                }
+                5 => {
+                    let expected = naive.path(u, v).map(|p| p.iter().map(|&x| val[x]).sum::<u64>());
+                    assert_eq!(sum.path_fold(u, v).map(|s| s.0.0), expected);
+                    if expected.is_some() {
+                        naive.evert(u);
+                        lct.evert(u);
+                    }
+                    assert_eq!(sum.get(v).0.0, val[v]);
+                }
+                _ => {
+                    assert_eq!(lct.lca(u, v), naive.lca(u, v));
+                    assert_eq!(sum.lca(u, v), naive.lca(u, v));
+                    assert_eq!(lct.root(u), naive.root_of(u));
+                }
+            }
+        }
+    }
+}
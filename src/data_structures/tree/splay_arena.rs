@@ -0,0 +1,128 @@
+use crate::algebra::*;
+
+pub const NIL: usize = usize::MAX;
+
+pub struct Node<T, E> {
+    pub val: T,
+    // fold of the splay subtree, in order
+    pub fold: T,
+    pub lazy: Option<E>,
+    pub rev: bool,
+    pub size: usize,
+    pub ch: [usize; 2],
+    pub par: usize,
+}
+
+impl<T: Clone, E> Node<T, E> {
+    pub fn new(val: T) -> Self {
+        Node { fold: val.clone(), val, lazy: None, rev: false, size: 1, ch: [NIL; 2], par: NIL }
+    }
+}
+
+// splay trees over an arena of `Node`s linked by index
+pub trait SplayArena {
+    type T: Monoid + Effect<Self::E>;
+    type E: Monoid;
+
+    fn node(&self, x: usize) -> &Node<Self::T, Self::E>;
+    fn node_mut(&mut self, x: usize) -> &mut Node<Self::T, Self::E>;
+
+    // whether `x` is the root of its splay tree
+    fn is_root(&self, x: usize) -> bool { self.node(x).par == NIL }
+
+    // whether actions apply to the value of `x`, or only pass through it
+    fn acts_on(&self, _x: usize) -> bool { true }
+
+    // pushes the pending updates of `x` to its children
+    fn push(&mut self, x: usize) { self.push_action(x) }
+
+    fn fold_of(&self, x: usize) -> Self::T {
+        if x == NIL { Self::T::identity() } else { self.node(x).fold.clone() }
+    }
+
+    fn size_of(&self, x: usize) -> usize {
+        if x == NIL { 0 } else { self.node(x).size }
+    }
+
+    fn fix(&mut self, x: usize) {
+        let [l, r] = self.node(x).ch;
+        let size = self.size_of(l) + self.size_of(r) + 1;
+        let fold = self.fold_of(l).op(&self.node(x).val).op(&self.fold_of(r));
+        let n = self.node_mut(x);
+        n.size = size;
+        n.fold = fold;
+    }
+
+    fn effect(&mut self, x: usize, e: &Self::E) {
+        if x == NIL { return }
+        let acts = self.acts_on(x);
+        let n = self.node_mut(x);
+        if acts { n.val = n.val.effect(e); }
+        n.fold = n.fold.effect(e);
+        n.lazy = Some(match n.lazy.take() {
+            Some(l) => l.op(e),
+            None => e.clone(),
+        });
+    }
+
+    fn push_action(&mut self, x: usize) {
+        if let Some(e) = self.node_mut(x).lazy.take() {
+            let [l, r] = self.node(x).ch;
+            self.effect(l, &e);
+            self.effect(r, &e);
+        }
+    }
+
+    fn rotate(&mut self, x: usize) {
+        let p = self.node(x).par;
+        let g = self.node(p).par;
+        let dir = if self.node(p).ch[0] == x { 0 } else { 1 };
+        let c = self.node(x).ch[dir ^ 1];
+        self.node_mut(p).ch[dir] = c;
+        if c != NIL { self.node_mut(c).par = p; }
+        self.node_mut(x).ch[dir ^ 1] = p;
+        self.node_mut(p).par = x;
+        self.node_mut(x).par = g;
+        // `g` may only be a path parent, whose children are unchanged
+        if g != NIL {
+            if self.node(g).ch[0] == p { self.node_mut(g).ch[0] = x; }
+            else if self.node(g).ch[1] == p { self.node_mut(g).ch[1] = x; }
+        }
+        self.fix(p);
+        self.fix(x);
+    }
+
+    fn splay(&mut self, x: usize) {
+        let mut stack = vec![x];
+        let mut y = x;
+        while !self.is_root(y) {
+            y = self.node(y).par;
+            stack.push(y);
+        }
+        while let Some(y) = stack.pop() { self.push(y); }
+        while !self.is_root(x) {
+            let p = self.node(x).par;
+            if !self.is_root(p) {
+                let g = self.node(p).par;
+                if (self.node(g).ch[0] == p) == (self.node(p).ch[0] == x) { self.rotate(p); }
+                else { self.rotate(x); }
+            }
+            self.rotate(x);
+        }
+    }
+
+    // leftmost node of the splay tree containing `x`, splayed to the root
+    fn leftmost(&mut self, x: usize) -> usize {
+        self.splay(x);
+        let mut x = x;
+        loop {
+            self.push(x);
+            match self.node(x).ch[0] {
+                NIL => break,
+                l => x = l,
+            }
+        }
+        self.splay(x);
+        x
+    }
+}
@@ -1,2 +1,6 @@
 pub mod union_find;
+pub mod weighted_union_find;
+pub mod monoid_union_find;
+pub mod undo_union_find;
+pub mod offline_dynamic_connectivity;
 pub mod persistent_union_find;
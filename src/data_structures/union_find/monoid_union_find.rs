@@ -0,0 +1,92 @@
+use crate::algebra::*;
+
+use std::cell::RefCell;
+
+pub struct MonoidUnionFind<T: Monoid> {
+    par: RefCell<Vec<usize>>,
+    sz: Vec<usize>,
+    data: Vec<T>,
+}
+
+impl<T: Monoid> MonoidUnionFind<T> {
+    pub fn new(data: Vec<T>) -> Self {
+        let n = data.len();
+        Self {
+            par: RefCell::new((0..n).collect()),
+            sz: vec![1; n],
+            data,
+        }
+    }
+
+    pub fn root(&self, v: usize) -> usize {
+        let p = self.par.borrow()[v];
+        if p == v { v }
+        else {
+            let r = self.root(p);
+            self.par.borrow_mut()[v] = r;
+            r
+        }
+    }
+
+    pub fn same(&self, a: usize, b: usize) -> bool {
+        self.root(a) == self.root(b)
+    }
+
+    pub fn size(&self, v: usize) -> usize {
+        self.sz[self.root(v)]
+    }
+
+    pub fn fold(&self, v: usize) -> &T {
+        &self.data[self.root(v)]
+    }
+
+    pub fn op_mut(&mut self, v: usize, x: &T) {
+        let r = self.root(v);
+        self.data[r] = self.data[r].op(x);
+    }
+
+    // the aggregate of the merged component is fold(a).op(fold(b))
+    pub fn unite(&mut self, a: usize, b: usize) -> Option<(usize, usize)> {
+        let a = self.root(a);
+        let b = self.root(b);
+        if a == b { return None }
+        let merged = self.data[a].op(&self.data[b]);
+        let (p, c) = if self.sz[a] < self.sz[b] { (b, a) } else { (a, b) };
+        self.par.borrow_mut()[c] = p;
+        self.sz[p] += self.sz[c];
+        self.data[p] = merged;
+        Some((p, c))
+    }
+}
+
+#[cfg(test)]
+mod monoid_union_find_test {
+    use crate::algebra::*;
+    use crate::data_structures::union_find::monoid_union_find::MonoidUnionFind;
+
+    #[derive(Clone)]
+    struct Mx(i64);
+
+    impl Magma for Mx {
+        fn op(&self, right: &Self) -> Self { Mx(std::cmp::max(self.0, right.0)) }
+    }
+    impl Associative for Mx {}
+    impl Unital for Mx {
+        fn identity() -> Self { Mx(i64::MIN) }
+    }
+
+    #[test]
+    fn component_max_test() {
+        let mut uf = MonoidUnionFind::new(vec![Mx(5), Mx(1), Mx(8), Mx(3)]);
+        uf.unite(1, 3);
+        assert_eq!(uf.fold(1).0, 3);
+        uf.op_mut(3, &Mx(6));
+        assert_eq!(uf.fold(1).0, 6);
+        uf.unite(0, 3);
+        assert_eq!(uf.fold(1).0, 6);
+        assert_eq!(uf.fold(2).0, 8);
+        uf.unite(2, 1);
+        assert_eq!(uf.fold(0).0, 8);
+        assert_eq!(uf.size(0), 4);
+    }
+}
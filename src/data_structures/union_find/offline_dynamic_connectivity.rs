@@ -0,0 +1,138 @@
+use crate::data_structures::union_find::undo_union_find::UndoUnionFind;
+
+use std::collections::HashMap;
+
+enum Query {
+    Add(usize, usize),
+    Remove(usize, usize),
+    Same(usize, usize),
+    Count,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Answer {
+    Same(bool),
+    Count(usize),
+}
+
+pub struct OfflineDynamicConnectivity {
+    n: usize,
+    query: Vec<Query>,
+}
+
+impl OfflineDynamicConnectivity {
+    pub fn new(n: usize) -> Self {
+        Self { n, query: Vec::new() }
+    }
+
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        self.query.push(Query::Add(std::cmp::min(u, v), std::cmp::max(u, v)));
+    }
+
+    pub fn remove_edge(&mut self, u: usize, v: usize) {
+        self.query.push(Query::Remove(std::cmp::min(u, v), std::cmp::max(u, v)));
+    }
+
+    pub fn same(&mut self, u: usize, v: usize) {
+        self.query.push(Query::Same(u, v));
+    }
+
+    pub fn count(&mut self) {
+        self.query.push(Query::Count);
+    }
+
+    pub fn solve(&self) -> Vec<Answer> {
+        let q = self.query.len();
+        let sz = std::cmp::max(q, 1).next_power_of_two();
+        let mut seg = vec![Vec::new(); sz << 1];
+        let mut alive: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        let mut push = |(u, v): (usize, usize), mut l: usize, mut r: usize| {
+            l += sz;
+            r += sz;
+            while l < r {
+                if l & 1 == 1 { seg[l].push((u, v)); l += 1; }
+                if r & 1 == 1 { r -= 1; seg[r].push((u, v)); }
+                l >>= 1;
+                r >>= 1;
+            }
+        };
+        for (t, query) in self.query.iter().enumerate() {
+            match *query {
+                Query::Add(u, v) => alive.entry((u, v)).or_default().push(t),
+                Query::Remove(u, v) => {
+                    let s = alive.get_mut(&(u, v)).and_then(|st| st.pop()).expect("removing an absent edge");
+                    push((u, v), s, t);
+                }
+                _ => {}
+            }
+        }
+        for (e, st) in alive.into_iter() {
+            for s in st { push(e, s, q); }
+        }
+        let mut uf = UndoUnionFind::new(self.n);
+        let mut ans = Vec::new();
+        self.dfs(1, sz, &seg, &mut uf, &mut ans);
+        ans
+    }
+
+    fn dfs(&self, k: usize, sz: usize, seg: &[Vec<(usize, usize)>], uf: &mut UndoUnionFind, ans: &mut Vec<Answer>) {
+        if k >= sz && k - sz >= self.query.len() { return }
+        let snap = uf.snapshot();
+        for &(u, v) in seg[k].iter() { uf.unite(u, v); }
+        if k >= sz {
+            match self.query[k - sz] {
+                Query::Same(u, v) => ans.push(Answer::Same(uf.same(u, v))),
+                Query::Count => ans.push(Answer::Count(uf.count())),
+                _ => {}
+            }
+        }
+        else {
+            self.dfs(k << 1, sz, seg, uf, ans);
+            self.dfs((k << 1) + 1, sz, seg, uf, ans);
+        }
+        uf.rollback(snap);
+    }
+}
+
+#[cfg(test)]
+mod offline_dynamic_connectivity_test {
+    use crate::data_structures::union_find::offline_dynamic_connectivity::{ OfflineDynamicConnectivity, Answer };
+    use crate::data_structures::union_find::union_find::UnionFind;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(36);
+        let n = 8;
+        let mut dc = OfflineDynamicConnectivity::new(n);
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        let mut expected = Vec::new();
+        for _ in 0..300 {
+            let u = (rng.rand_u64() % n as u64) as usize;
+            let v = (rng.rand_u64() % n as u64) as usize;
+            match rng.rand_u64() % 4 {
+                0 => {
+                    dc.add_edge(u, v);
+                    edges.push((u, v));
+                }
+                1 if !edges.is_empty() => {
+                    let (a, b) = edges.swap_remove((rng.rand_u64() % edges.len() as u64) as usize);
+                    dc.remove_edge(b, a);
+                }
+                _ => {
+                    let mut uf = UnionFind::new(n);
+                    for &(a, b) in edges.iter() { uf.unite(a, b); }
+                    if rng.rand_u64() & 1 == 0 {
+                        dc.same(u, v);
+                        expected.push(Answer::Same(uf.same(u, v)));
+                    }
+                    else {
+                        dc.count();
+                        expected.push(Answer::Count(uf.groups().len()));
+                    }
+                }
+            }
+        }
+        assert_eq!(dc.solve(), expected);
+    }
+}
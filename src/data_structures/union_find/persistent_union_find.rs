@@ -1,3 +1,4 @@
+use crate::algebra::*;
 use crate::data_structures::containers::rerooting_persistent_array::RerootingPersistentArray;
 
 use std::cell::RefCell;
@@ -5,10 +6,14 @@ use std::cell::RefCell;
 pub struct ConchonFilliatrePersistentUnionFind {
     rank: RerootingPersistentArray<u32>,
     par: RefCell<RerootingPersistentArray<usize>>,
+    sz: RerootingPersistentArray<usize>,
+    count: usize,
 }
 
 impl Clone for ConchonFilliatrePersistentUnionFind {
-    fn clone(&self) -> Self { Self { rank: self.rank.clone(), par: self.par.clone() }}
+    fn clone(&self) -> Self {
+        Self { rank: self.rank.clone(), par: self.par.clone(), sz: self.sz.clone(), count: self.count }
+    }
 }
 
 impl ConchonFilliatrePersistentUnionFind {
@@ -16,6 +21,8 @@ impl ConchonFilliatrePersistentUnionFind {
         Self {
             rank: RerootingPersistentArray::from_iter(std::iter::repeat(0).take(n)),
             par: RefCell::new(RerootingPersistentArray::from_iter(0..n)),
+            sz: RerootingPersistentArray::from_iter(vec![1; n]),
+            count: n,
         }
     }
 
@@ -30,6 +37,16 @@ impl ConchonFilliatrePersistentUnionFind {
             r
         }
     }
+
+    pub fn same(&self, u: usize, v: usize) -> bool {
+        self.root(u) == self.root(v)
+    }
+
+    pub fn size(&self, v: usize) -> usize {
+        *self.sz.get(self.root(v))
+    }
+
+    pub fn count(&self) -> usize { self.count }
     
     pub fn union(&self, u: usize, v: usize) -> Self {
         let u = self.root(u);
@@ -40,20 +57,182 @@ impl ConchonFilliatrePersistentUnionFind {
         else {
             let ru = *self.rank.get(u);
             let rv = *self.rank.get(v);
+            let su = *self.sz.get(u);
+            let s = su + *self.sz.get(v);
+            let count = self.count - 1;
             match ru.cmp(&rv) {
                 std::cmp::Ordering::Greater => Self {
                     rank: self.rank.clone(),
                     par: RefCell::new(self.par.borrow().set(v, u)),
+                    sz: self.sz.set(u, s),
+                    count,
                 },
                 std::cmp::Ordering::Equal => Self {
                     rank: self.rank.set(u, ru + 1u32),
                     par: RefCell::new(self.par.borrow().set(v, u)),
+                    sz: self.sz.set(u, s),
+                    count,
                 },
                 std::cmp::Ordering::Less => Self {
                     rank: self.rank.clone(),
                     par: RefCell::new(self.par.borrow().set(u, v)),
+                    sz: self.sz.set(v, s),
+                    count,
                 }
             }
         }
     }
 }
+
+pub struct PersistentWeightedUnionFind<G: Monoid + Inv> {
+    rank: RerootingPersistentArray<u32>,
+    par: RefCell<RerootingPersistentArray<usize>>,
+    pot: RefCell<RerootingPersistentArray<G>>,
+    sz: RerootingPersistentArray<usize>,
+    count: usize,
+}
+
+impl<G: Monoid + Inv> Clone for PersistentWeightedUnionFind<G> {
+    fn clone(&self) -> Self {
+        Self {
+            rank: self.rank.clone(),
+            par: self.par.clone(),
+            pot: self.pot.clone(),
+            sz: self.sz.clone(),
+            count: self.count,
+        }
+    }
+}
+
+impl<G: Monoid + Inv> PersistentWeightedUnionFind<G> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            rank: RerootingPersistentArray::from_iter(vec![0; n]),
+            par: RefCell::new(RerootingPersistentArray::from_iter(0..n)),
+            pot: RefCell::new(RerootingPersistentArray::from_iter((0..n).map(|_| G::identity()))),
+            sz: RerootingPersistentArray::from_iter(vec![1; n]),
+            count: n,
+        }
+    }
+
+    pub fn root(&self, v: usize) -> usize {
+        let p = *self.par.borrow().get(v);
+        if p == v {
+            p
+        }
+        else {
+            let r = self.root(p);
+            let w = {
+                let pot = self.pot.borrow();
+                let wp = pot.get(p).clone();
+                let w = wp.op(&pot.get(v));
+                w
+            };
+            self.pot.replace_with(|pot| pot.set(v, w));
+            self.par.replace_with(|par| par.set(v, r));
+            r
+        }
+    }
+
+    // x_root^{-1} x_v
+    pub fn potential(&self, v: usize) -> G {
+        self.root(v);
+        let pot = self.pot.borrow();
+        let w = pot.get(v).clone();
+        w
+    }
+
+    // x_u^{-1} x_v
+    pub fn diff(&self, u: usize, v: usize) -> Option<G> {
+        if self.root(u) != self.root(v) { None }
+        else { Some(self.potential(u).inv().op(&self.potential(v))) }
+    }
+
+    pub fn same(&self, u: usize, v: usize) -> bool {
+        self.root(u) == self.root(v)
+    }
+
+    pub fn size(&self, v: usize) -> usize {
+        *self.sz.get(self.root(v))
+    }
+
+    pub fn count(&self) -> usize { self.count }
+
+    // declares x_u^{-1} x_v = d, and returns None if it contradicts the known relations
+    pub fn union(&self, u: usize, v: usize, d: G) -> Option<Self> where G: PartialEq {
+        let (ru, rv) = (self.root(u), self.root(v));
+        if ru == rv {
+            return if self.diff(u, v) == Some(d) { Some(self.clone()) } else { None };
+        }
+        let w = self.potential(u).op(&d).op(&self.potential(v).inv());
+        let rank_u = *self.rank.get(ru);
+        let rank_v = *self.rank.get(rv);
+        let su = *self.sz.get(ru);
+        let s = su + *self.sz.get(rv);
+        let (parent, child, w) = if rank_u < rank_v { (rv, ru, w.inv()) } else { (ru, rv, w) };
+        Some(Self {
+            rank: if rank_u == rank_v { self.rank.set(ru, rank_u + 1) } else { self.rank.clone() },
+            par: RefCell::new(self.par.borrow().set(child, parent)),
+            pot: RefCell::new(self.pot.borrow().set(child, w)),
+            sz: self.sz.set(parent, s),
+            count: self.count - 1,
+        })
+    }
+}
+
+#[cfg(test)]
+mod persistent_union_find_test {
+    use crate::algebra::*;
+    use crate::data_structures::union_find::persistent_union_find::*;
+
+    #[test]
+    fn versions_test() {
+        let v0 = ConchonFilliatrePersistentUnionFind::new(5);
+        let v1 = v0.union(0, 1);
+        let v2 = v1.union(2, 3);
+        let v3 = v1.union(1, 4);
+        let v4 = v2.union(0, 3);
+        assert!(!v0.same(0, 1));
+        assert!(v1.same(0, 1));
+        assert_eq!((v1.size(0), v1.count()), (2, 4));
+        assert!(!v3.same(0, 2));
+        assert_eq!((v3.size(4), v3.count()), (3, 3));
+        assert!(v4.same(1, 2));
+        assert_eq!((v4.size(3), v4.count()), (4, 2));
+        assert!(!v2.same(1, 2));
+        assert_eq!(v2.size(2), 2);
+        assert_eq!(v0.size(4), 1);
+    }
+
+    #[derive(Clone, PartialEq, Debug)]
+    struct Am(i64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+    impl Inv for Am {
+        fn inv(&self) -> Self { Am(-self.0) }
+    }
+
+    #[test]
+    fn weighted_versions_test() {
+        let v0 = PersistentWeightedUnionFind::new(4);
+        let v1 = v0.union(0, 1, Am(3)).unwrap();
+        let v2 = v1.union(1, 2, Am(4)).unwrap();
+        let v3 = v1.union(2, 1, Am(1)).unwrap();
+        assert_eq!(v2.diff(0, 2), Some(Am(7)));
+        assert_eq!(v3.diff(0, 2), Some(Am(2)));
+        assert_eq!(v1.diff(0, 2), None);
+        assert!(v2.union(2, 0, Am(-7)).is_some());
+        assert!(v2.union(2, 0, Am(7)).is_none());
+        let v4 = v3.union(3, 0, Am(5)).unwrap();
+        assert_eq!(v4.diff(3, 2), Some(Am(7)));
+        assert_eq!((v4.size(2), v4.count()), (4, 1));
+        assert_eq!((v3.size(2), v3.count()), (3, 2));
+        assert_eq!(v2.diff(2, 1), Some(Am(-4)));
+    }
+}
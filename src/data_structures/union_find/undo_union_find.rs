@@ -0,0 +1,83 @@
+pub struct UndoUnionFind {
+    par: Vec<usize>,
+    sz: Vec<usize>,
+    history: Vec<Option<(usize, usize)>>,
+    count: usize,
+}
+
+impl UndoUnionFind {
+    pub fn new(n: usize) -> Self {
+        Self { par: (0..n).collect(), sz: vec![1; n], history: Vec::new(), count: n }
+    }
+
+    pub fn root(&self, mut v: usize) -> usize {
+        while self.par[v] != v { v = self.par[v]; }
+        v
+    }
+
+    pub fn same(&self, a: usize, b: usize) -> bool {
+        self.root(a) == self.root(b)
+    }
+
+    pub fn size(&self, v: usize) -> usize {
+        self.sz[self.root(v)]
+    }
+
+    pub fn count(&self) -> usize { self.count }
+
+    pub fn unite(&mut self, a: usize, b: usize) -> bool {
+        let mut a = self.root(a);
+        let mut b = self.root(b);
+        if a == b {
+            self.history.push(None);
+            return false
+        }
+        if self.sz[a] < self.sz[b] { std::mem::swap(&mut a, &mut b); }
+        self.par[b] = a;
+        self.sz[a] += self.sz[b];
+        self.count -= 1;
+        self.history.push(Some((a, b)));
+        true
+    }
+
+    pub fn undo(&mut self) {
+        if let Some((a, b)) = self.history.pop().expect("nothing to undo") {
+            self.par[b] = b;
+            self.sz[a] -= self.sz[b];
+            self.count += 1;
+        }
+    }
+
+    pub fn snapshot(&self) -> usize { self.history.len() }
+
+    pub fn rollback(&mut self, snap: usize) {
+        while self.history.len() > snap { self.undo(); }
+    }
+}
+
+#[cfg(test)]
+mod undo_union_find_test {
+    use crate::data_structures::union_find::undo_union_find::UndoUnionFind;
+
+    #[test]
+    fn rollback_test() {
+        let mut uf = UndoUnionFind::new(5);
+        assert!(uf.unite(0, 1));
+        let snap = uf.snapshot();
+        assert!(uf.unite(2, 3));
+        assert!(uf.unite(1, 3));
+        assert!(!uf.unite(0, 2));
+        assert_eq!(uf.size(2), 4);
+        assert_eq!(uf.count(), 2);
+        uf.undo();
+        assert!(uf.same(0, 2));
+        uf.rollback(snap);
+        assert!(uf.same(0, 1));
+        assert!(!uf.same(2, 3));
+        assert_eq!(uf.size(0), 2);
+        assert_eq!(uf.count(), 4);
+        uf.rollback(0);
+        assert!(!uf.same(0, 1));
+        assert_eq!(uf.count(), 5);
+    }
+}
@@ -38,4 +38,46 @@ impl UnionFind {
             Some((a, b))
         }
     }
+
+    pub fn same(&self, a: usize, b: usize) -> bool {
+        self.root(a) == self.root(b)
+    }
+
+    pub fn size(&self, v: usize) -> usize {
+        self.sz[self.root(v)]
+    }
+
+    pub fn groups(&self) -> Vec<Vec<usize>> {
+        let n = self.sz.len();
+        let mut idx = vec![None; n];
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for v in 0..n {
+            let r = self.root(v);
+            let i = *idx[r].get_or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[i].push(v);
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod union_find_test {
+    use crate::data_structures::union_find::union_find::UnionFind;
+
+    #[test]
+    fn union_find_test() {
+        let mut uf = UnionFind::new(6);
+        assert_eq!(uf.unite(0, 1), Some((0, 1)));
+        assert_eq!(uf.unite(2, 3), Some((2, 3)));
+        assert_eq!(uf.unite(1, 3), Some((0, 2)));
+        assert_eq!(uf.unite(0, 2), None);
+        assert!(uf.same(1, 2));
+        assert!(!uf.same(1, 4));
+        assert_eq!(uf.size(3), 4);
+        assert_eq!(uf.size(5), 1);
+        assert_eq!(uf.groups(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+    }
 }
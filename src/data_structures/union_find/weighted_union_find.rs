@@ -0,0 +1,143 @@
+use crate::algebra::*;
+
+use std::cell::RefCell;
+
+pub struct WeightedUnionFind<G: Monoid + Inv> {
+    par: RefCell<Vec<usize>>,
+    pot: RefCell<Vec<G>>,
+    sz: Vec<usize>,
+}
+
+impl<G: Monoid + Inv> WeightedUnionFind<G> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            par: RefCell::new((0..n).collect()),
+            pot: RefCell::new(vec![G::identity(); n]),
+            sz: vec![1; n],
+        }
+    }
+
+    pub fn root(&self, v: usize) -> usize {
+        let p = self.par.borrow()[v];
+        if p == v { v }
+        else {
+            let r = self.root(p);
+            let w = self.pot.borrow()[p].op(&self.pot.borrow()[v]);
+            self.pot.borrow_mut()[v] = w;
+            self.par.borrow_mut()[v] = r;
+            r
+        }
+    }
+
+    // x_root^{-1} x_v
+    pub fn potential(&self, v: usize) -> G {
+        self.root(v);
+        self.pot.borrow()[v].clone()
+    }
+
+    // x_u^{-1} x_v
+    pub fn diff(&self, u: usize, v: usize) -> Option<G> {
+        if self.root(u) != self.root(v) { None }
+        else { Some(self.potential(u).inv().op(&self.potential(v))) }
+    }
+
+    pub fn same(&self, a: usize, b: usize) -> bool {
+        self.root(a) == self.root(b)
+    }
+
+    pub fn size(&self, v: usize) -> usize {
+        self.sz[self.root(v)]
+    }
+
+    // declares x_u^{-1} x_v = d, and returns false if it contradicts the known relations
+    pub fn unite(&mut self, u: usize, v: usize, d: G) -> bool where G: PartialEq {
+        let (ru, rv) = (self.root(u), self.root(v));
+        if ru == rv {
+            return self.diff(u, v) == Some(d);
+        }
+        let w = self.potential(u).op(&d).op(&self.potential(v).inv());
+        if self.sz[ru] < self.sz[rv] {
+            self.par.borrow_mut()[ru] = rv;
+            self.pot.borrow_mut()[ru] = w.inv();
+            self.sz[rv] += self.sz[ru];
+        }
+        else {
+            self.par.borrow_mut()[rv] = ru;
+            self.pot.borrow_mut()[rv] = w;
+            self.sz[ru] += self.sz[rv];
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod weighted_union_find_test {
+    use crate::algebra::*;
+    use crate::data_structures::union_find::weighted_union_find::WeightedUnionFind;
+
+    #[derive(Clone, PartialEq, Debug)]
+    struct Am(i64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+    impl Inv for Am {
+        fn inv(&self) -> Self { Am(-self.0) }
+    }
+
+    // permutations of 3 elements under composition, a non-commutative group
+    #[derive(Clone, PartialEq, Debug)]
+    struct Perm([usize; 3]);
+
+    impl Magma for Perm {
+        fn op(&self, right: &Self) -> Self { Perm([right.0[self.0[0]], right.0[self.0[1]], right.0[self.0[2]]]) }
+    }
+    impl Associative for Perm {}
+    impl Unital for Perm {
+        fn identity() -> Self { Perm([0, 1, 2]) }
+    }
+    impl Inv for Perm {
+        fn inv(&self) -> Self {
+            let mut p = [0; 3];
+            for i in 0..3 { p[self.0[i]] = i; }
+            Perm(p)
+        }
+    }
+
+    #[test]
+    fn potential_test() {
+        let mut uf = WeightedUnionFind::new(5);
+        assert!(uf.unite(0, 1, Am(3)));
+        assert!(uf.unite(2, 1, Am(-2)));
+        assert!(uf.unite(3, 4, Am(7)));
+        assert_eq!(uf.diff(0, 2), Some(Am(5)));
+        assert_eq!(uf.diff(2, 0), Some(Am(-5)));
+        assert_eq!(uf.diff(0, 3), None);
+        assert!(uf.unite(4, 0, Am(1)));
+        assert_eq!(uf.diff(3, 2), Some(Am(13)));
+        assert!(uf.unite(3, 2, Am(13)));
+        assert!(!uf.unite(3, 2, Am(12)));
+        assert_eq!(uf.size(1), 5);
+    }
+
+    #[test]
+    fn non_commutative_test() {
+        let x = [Perm([1, 2, 0]), Perm([0, 2, 1]), Perm([2, 1, 0]), Perm([1, 0, 2]), Perm([2, 0, 1])];
+        let d = |u: usize, v: usize| x[u].inv().op(&x[v]);
+        let mut uf = WeightedUnionFind::new(5);
+        assert!(uf.unite(0, 1, d(0, 1)));
+        assert!(uf.unite(3, 2, d(3, 2)));
+        assert!(uf.unite(4, 3, d(4, 3)));
+        assert!(uf.unite(1, 4, d(1, 4)));
+        for u in 0..5 {
+            for v in 0..5 {
+                assert_eq!(uf.diff(u, v), Some(d(u, v)));
+            }
+        }
+        assert!(!uf.unite(0, 2, d(0, 2).op(&Perm([1, 0, 2]))));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod bit_vector;
+pub mod wavelet_matrix;
@@ -0,0 +1,126 @@
+use crate::data_structures::set::bitset::Bitset;
+
+// the position of the `k`-th set bit of `w`, found byte by byte
+fn select_word(w: u64, mut k: usize) -> usize {
+    let mut s = 0;
+    loop {
+        let c = ((w >> s) & 0xff).count_ones() as usize;
+        if k < c { break }
+        k -= c;
+        s += 8;
+    }
+    let mut b = (w >> s) & 0xff;
+    for _ in 0..k { b &= b - 1; }
+    s + b.trailing_zeros() as usize
+}
+
+// every `SAMPLE`-th one and zero has the index of its word stored
+const SAMPLE: usize = 256;
+
+// the first word in `lo..hi` after which `count` exceeds `k`, where `count(w)` is nondecreasing
+fn search_words<F: Fn(usize) -> usize>(mut lo: usize, mut hi: usize, k: usize, count: F) -> usize {
+    while hi - lo > 1 {
+        let mid = (lo + hi) >> 1;
+        if count(mid) <= k { lo = mid } else { hi = mid }
+    }
+    lo
+}
+
+// static bit vector with O(1) rank. select binary-searches only the words between two
+// sampled ones (or zeros), so it is O(1) unless they are far apart, and O(log n) at worst
+pub struct BitVector {
+    bits: Bitset,
+    // rank[w] is the number of ones in the words before `w`
+    rank: Vec<usize>,
+    // hint1[j] is the word holding the `j * SAMPLE`-th one, and hint0 likewise for zeros
+    hint1: Vec<usize>,
+    hint0: Vec<usize>,
+}
+
+impl BitVector {
+    pub fn new(bits: Bitset) -> Self {
+        let n = bits.len();
+        let mut rank = Vec::with_capacity(bits.words().len() + 1);
+        let (mut hint1, mut hint0) = (Vec::new(), Vec::new());
+        rank.push(0);
+        for (w, word) in bits.words().iter().enumerate() {
+            let ones = *rank.last().unwrap();
+            let zeros = (w << 6) - ones;
+            let c = word.count_ones() as usize;
+            while hint1.len() * SAMPLE < ones + c { hint1.push(w); }
+            while hint0.len() * SAMPLE < zeros + std::cmp::min(64, n - (w << 6)) - c { hint0.push(w); }
+            rank.push(ones + c);
+        }
+        BitVector { bits, rank, hint1, hint0 }
+    }
+
+    pub fn len(&self) -> usize { self.bits.len() }
+
+    pub fn is_empty(&self) -> bool { self.bits.is_empty() }
+
+    pub fn get(&self, i: usize) -> bool { self.bits.get(i) }
+
+    pub fn count_ones(&self) -> usize { *self.rank.last().unwrap() }
+
+    pub fn count_zeros(&self) -> usize { self.len() - self.count_ones() }
+
+    // the number of ones in [0, i)
+    pub fn rank1(&self, i: usize) -> usize {
+        assert!(i <= self.len());
+        let w = i >> 6;
+        let r = self.rank[w];
+        if i & 63 == 0 { r }
+        else { r + (self.bits.words()[w] & ((1u64 << (i & 63)) - 1)).count_ones() as usize }
+    }
+
+    // the number of zeros in [0, i)
+    pub fn rank0(&self, i: usize) -> usize { i - self.rank1(i) }
+
+    // the position of the `k`-th one (0-indexed)
+    pub fn select1(&self, k: usize) -> Option<usize> {
+        if k >= self.count_ones() { return None }
+        let j = k / SAMPLE;
+        let hi = self.hint1.get(j + 1).map_or(self.rank.len() - 1, |&h| h + 1);
+        let w = search_words(self.hint1[j], hi, k, |w| self.rank[w]);
+        Some((w << 6) + select_word(self.bits.words()[w], k - self.rank[w]))
+    }
+
+    // the position of the `k`-th zero (0-indexed)
+    pub fn select0(&self, k: usize) -> Option<usize> {
+        if k >= self.count_zeros() { return None }
+        let j = k / SAMPLE;
+        let hi = self.hint0.get(j + 1).map_or(self.rank.len() - 1, |&h| h + 1);
+        let w = search_words(self.hint0[j], hi, k, |w| (w << 6) - self.rank[w]);
+        Some((w << 6) + select_word(!self.bits.words()[w], k - ((w << 6) - self.rank[w])))
+    }
+}
+
+#[cfg(test)]
+mod bit_vector_test {
+    use crate::data_structures::set::bitset::Bitset;
+    use crate::data_structures::wavelet_matrix::bit_vector::BitVector;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(7);
+        for n in [0, 1, 63, 64, 65, 200, 1000, 5000] {
+            let mut bits = Bitset::new(n);
+            let sparse = rng.rand_u64() & 1 == 0;
+            let v = (0..n).map(|i| if sparse { i % 700 == 3 } else { rng.rand_u64() & 3 == 0 }).collect::<Vec<_>>();
+            for (i, &b) in v.iter().enumerate() { bits.set(i, b); }
+            let bv = BitVector::new(bits);
+            assert_eq!(bv.len(), n);
+            let ones = (0..n).filter(|&i| v[i]).collect::<Vec<_>>();
+            let zeros = (0..n).filter(|&i| !v[i]).collect::<Vec<_>>();
+            for i in 0..n + 1 {
+                assert_eq!(bv.rank1(i), v[..i].iter().filter(|&&b| b).count());
+                assert_eq!(bv.rank0(i), i - bv.rank1(i));
+            }
+            for k in 0..n + 1 {
+                assert_eq!(bv.select1(k), ones.get(k).cloned());
+                assert_eq!(bv.select0(k), zeros.get(k).cloned());
+            }
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use crate::algebra::*;
+use crate::data_structures::set::bitset::Bitset;
+use crate::data_structures::wavelet_matrix::bit_vector::BitVector;
+use crate::data_structures::fenwick_tree::fenwick_tree::FenwickTree;
+
+use std::ops::Range;
+
+pub struct WaveletMatrix {
+    len: usize,
+    bits: usize,
+    // levels[d] holds bit `bits - 1 - d` of the values, in the order after the first `d` levels
+    levels: Vec<BitVector>,
+    zeros: Vec<usize>,
+}
+
+impl WaveletMatrix {
+    pub fn new(vals: &[u64]) -> Self {
+        let max = vals.iter().max().cloned().unwrap_or(0);
+        let bits = std::cmp::max(1, 64 - max.leading_zeros() as usize);
+        let mut cur = vals.to_vec();
+        let mut levels = Vec::with_capacity(bits);
+        let mut zeros = Vec::with_capacity(bits);
+        for d in (0..bits).rev() {
+            let mut b = Bitset::new(cur.len());
+            for (i, &v) in cur.iter().enumerate() {
+                b.set(i, v >> d & 1 == 1);
+            }
+            let (mut next, ones): (Vec<_>, Vec<_>) = cur.iter().partition(|&&v| v >> d & 1 == 0);
+            zeros.push(next.len());
+            next.extend(ones);
+            levels.push(BitVector::new(b));
+            cur = next;
+        }
+        WaveletMatrix { len: vals.len(), bits, levels, zeros }
+    }
+
+    pub fn len(&self) -> usize { self.len }
+
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    fn bit(&self, x: u64, d: usize) -> bool {
+        x >> (self.bits - 1 - d) & 1 == 1
+    }
+
+    fn fits(&self, x: u64) -> bool {
+        self.bits == 64 || x >> self.bits == 0
+    }
+
+    // the position of `i` at level `d + 1`
+    fn next_pos(&self, d: usize, i: usize, b: bool) -> usize {
+        if b { self.zeros[d] + self.levels[d].rank1(i) }
+        else { self.levels[d].rank0(i) }
+    }
+
+    pub fn access(&self, mut i: usize) -> u64 {
+        assert!(i < self.len);
+        let mut res = 0;
+        for d in 0..self.bits {
+            let b = self.levels[d].get(i);
+            res = res << 1 | b as u64;
+            i = self.next_pos(d, i, b);
+        }
+        res
+    }
+
+    // the number of `x` in [0, r)
+    pub fn rank(&self, x: u64, r: usize) -> usize {
+        assert!(r <= self.len);
+        if !self.fits(x) { return 0 }
+        let (mut l, mut r) = (0, r);
+        for d in 0..self.bits {
+            let b = self.bit(x, d);
+            l = self.next_pos(d, l, b);
+            r = self.next_pos(d, r, b);
+        }
+        r - l
+    }
+
+    // the position of the `k`-th `x` (0-indexed)
+    pub fn select(&self, x: u64, k: usize) -> Option<usize> {
+        if k >= self.rank(x, self.len) { return None }
+        let mut p = 0;
+        for d in 0..self.bits {
+            p = self.next_pos(d, p, self.bit(x, d));
+        }
+        p += k;
+        for d in (0..self.bits).rev() {
+            p = if self.bit(x, d) { self.levels[d].select1(p - self.zeros[d]) }
+                else { self.levels[d].select0(p) }.unwrap();
+        }
+        Some(p)
+    }
+
+    // the `k`-th smallest value in the range (0-indexed)
+    pub fn kth_smallest(&self, ran: Range<usize>, mut k: usize) -> u64 {
+        assert!(ran.start <= ran.end && ran.end <= self.len);
+        assert!(k < ran.end - ran.start);
+        let Range { start: mut l, end: mut r } = ran;
+        let mut res = 0;
+        for d in 0..self.bits {
+            let z = self.levels[d].rank0(r) - self.levels[d].rank0(l);
+            let b = k >= z;
+            if b { k -= z; }
+            res = res << 1 | b as u64;
+            l = self.next_pos(d, l, b);
+            r = self.next_pos(d, r, b);
+        }
+        res
+    }
+
+    pub fn kth_largest(&self, ran: Range<usize>, k: usize) -> u64 {
+        assert!(ran.start <= ran.end && ran.end <= self.len);
+        let n = ran.end - ran.start;
+        assert!(k < n);
+        self.kth_smallest(ran, n - 1 - k)
+    }
+
+    // the number of values less than `upper` in the range
+    pub fn count_less(&self, ran: Range<usize>, upper: u64) -> usize {
+        assert!(ran.start <= ran.end && ran.end <= self.len);
+        if !self.fits(upper) { return ran.end - ran.start }
+        let Range { start: mut l, end: mut r } = ran;
+        let mut res = 0;
+        for d in 0..self.bits {
+            let b = self.bit(upper, d);
+            if b { res += self.levels[d].rank0(r) - self.levels[d].rank0(l); }
+            l = self.next_pos(d, l, b);
+            r = self.next_pos(d, r, b);
+        }
+        res
+    }
+
+    // the number of values in `val` in the range
+    pub fn range_freq(&self, ran: Range<usize>, val: Range<u64>) -> usize {
+        if val.start >= val.end { return 0 }
+        self.count_less(ran.clone(), val.end) - self.count_less(ran, val.start)
+    }
+
+    // the largest value less than `upper` in the range
+    pub fn prev_value(&self, ran: Range<usize>, upper: u64) -> Option<u64> {
+        match self.count_less(ran.clone(), upper) {
+            0 => None,
+            c => Some(self.kth_smallest(ran, c - 1)),
+        }
+    }
+
+    // the smallest value not less than `lower` in the range
+    pub fn next_value(&self, ran: Range<usize>, lower: u64) -> Option<u64> {
+        let c = self.count_less(ran.clone(), lower);
+        if c == ran.end - ran.start { None }
+        else { Some(self.kth_smallest(ran, c)) }
+    }
+}
+
+// wavelet matrix with a weight on each position, summed by the per-level fenwick trees
+pub struct WeightedWaveletMatrix<T: Monoid + Inv + Commutative> {
+    wm: WaveletMatrix,
+    // fens[d] is indexed by the positions at level `d + 1`
+    fens: Vec<FenwickTree<T>>,
+}
+
+impl<T: Monoid + Inv + Commutative> WeightedWaveletMatrix<T> {
+    pub fn new(vals: &[u64], weights: &[T]) -> Self {
+        assert_eq!(vals.len(), weights.len());
+        let wm = WaveletMatrix::new(vals);
+        let mut fens = Vec::with_capacity(wm.bits);
+        let mut pos = (0..vals.len()).collect::<Vec<_>>();
+        for d in 0..wm.bits {
+            let mut arr = vec![T::identity(); vals.len()];
+            for (i, p) in pos.iter_mut().enumerate() {
+                *p = wm.next_pos(d, *p, wm.bit(vals[i], d));
+                arr[*p] = weights[i].clone();
+            }
+            fens.push(FenwickTree::init(&arr));
+        }
+        WeightedWaveletMatrix { wm, fens }
+    }
+
+    pub fn matrix(&self) -> &WaveletMatrix { &self.wm }
+
+    // adds `w` to the weight of position `i`
+    pub fn add(&mut self, mut i: usize, w: &T) {
+        assert!(i < self.wm.len);
+        for d in 0..self.wm.bits {
+            i = self.wm.next_pos(d, i, self.wm.levels[d].get(i));
+            self.fens[d].add(i, w);
+        }
+    }
+
+    // the sum of the weights of the positions in the range whose values are less than `upper`
+    pub fn sum_less(&self, ran: Range<usize>, upper: u64) -> T {
+        assert!(ran.start <= ran.end && ran.end <= self.wm.len);
+        let wm = &self.wm;
+        let Range { start: mut l, end: mut r } = ran;
+        if !wm.fits(upper) {
+            let z = wm.zeros[0];
+            let (l0, r0) = (wm.levels[0].rank0(l), wm.levels[0].rank0(r));
+            return self.fens[0].fold(l0..r0).op(&self.fens[0].fold(z + l - l0..z + r - r0));
+        }
+        let mut res = T::identity();
+        for d in 0..wm.bits {
+            let b = wm.bit(upper, d);
+            if b {
+                res = res.op(&self.fens[d].fold(wm.levels[d].rank0(l)..wm.levels[d].rank0(r)));
+            }
+            l = wm.next_pos(d, l, b);
+            r = wm.next_pos(d, r, b);
+        }
+        res
+    }
+
+    // the sum of the weights of the positions in the range whose values are in `val`
+    pub fn sum(&self, ran: Range<usize>, val: Range<u64>) -> T {
+        if val.start >= val.end { return T::identity() }
+        self.sum_less(ran.clone(), val.end).op(&self.sum_less(ran, val.start).inv())
+    }
+}
+
+#[cfg(test)]
+mod wavelet_matrix_test {
+    use crate::algebra::*;
+    use crate::data_structures::wavelet_matrix::wavelet_matrix::{ WaveletMatrix, WeightedWaveletMatrix };
+    use crate::random::{ Random, Xorshift128 };
+
+    #[derive(Clone)]
+    struct Am(i64);
+
+    impl Magma for Am {
+        fn op(&self, right: &Self) -> Self { Am(self.0 + right.0) }
+    }
+    impl Associative for Am {}
+    impl Commutative for Am {}
+    impl Unital for Am {
+        fn identity() -> Self { Am(0) }
+    }
+    impl Inv for Am {
+        fn inv(&self) -> Self { Am(-self.0) }
+    }
+
+    #[test]
+    fn random_test() {
+        let mut rng = Xorshift128::new(1);
+        for &(n, m) in &[(1, 1), (50, 8), (100, 1000), (80, u64::MAX)] {
+            let v = (0..n).map(|_| if m == u64::MAX { rng.rand_u64() } else { rng.rand_u64() % m }).collect::<Vec<_>>();
+            let wm = WaveletMatrix::new(&v);
+            for (i, &x) in v.iter().enumerate() {
+                assert_eq!(wm.access(i), x);
+                assert_eq!(wm.rank(x, i), v[..i].iter().filter(|&&y| y == x).count());
+                let k = wm.rank(x, i);
+                assert_eq!(wm.select(x, k), Some(i));
+            }
+            assert_eq!(wm.select(v[0], n), None);
+            for _ in 0..200 {
+                let l = (rng.rand_u64() % (n as u64 + 1)) as usize;
+                let r = l + (rng.rand_u64() % ((n - l) as u64 + 1)) as usize;
+                let mut sorted = v[l..r].to_vec();
+                sorted.sort();
+                for k in 0..r - l {
+                    assert_eq!(wm.kth_smallest(l..r, k), sorted[k]);
+                    assert_eq!(wm.kth_largest(l..r, k), sorted[r - l - 1 - k]);
+                }
+                let a = if m == u64::MAX { rng.rand_u64() } else { rng.rand_u64() % (m + 1) };
+                let b = if m == u64::MAX { rng.rand_u64() } else { rng.rand_u64() % (m + 1) };
+                let (a, b) = (std::cmp::min(a, b), std::cmp::max(a, b));
+                assert_eq!(wm.range_freq(l..r, a..b), sorted.iter().filter(|&&x| a <= x && x < b).count());
+                assert_eq!(wm.prev_value(l..r, b), sorted.iter().rev().find(|&&x| x < b).cloned());
+                assert_eq!(wm.next_value(l..r, a), sorted.iter().find(|&&x| a <= x).cloned());
+            }
+        }
+    }
+
+    #[test]
+    fn weighted_test() {
+        let mut rng = Xorshift128::new(2);
+        let n = 100;
+        let v = (0..n).map(|_| rng.rand_u64() % 50).collect::<Vec<_>>();
+        let mut w = (0..n).map(|_| (rng.rand_u64() % 100) as i64).collect::<Vec<_>>();
+        let mut wm = WeightedWaveletMatrix::new(&v, &w.iter().map(|&x| Am(x)).collect::<Vec<_>>());
+        for _ in 0..500 {
+            if rng.rand_u64() & 1 == 0 {
+                let i = (rng.rand_u64() % n as u64) as usize;
+                let x = (rng.rand_u64() % 100) as i64 - 50;
+                wm.add(i, &Am(x));
+                w[i] += x;
+            }
+            else {
+                let l = (rng.rand_u64() % (n as u64 + 1)) as usize;
+                let r = l + (rng.rand_u64() % ((n - l) as u64 + 1)) as usize;
+                let a = rng.rand_u64() % 60;
+                let b = a + rng.rand_u64() % 60;
+                let expect = (l..r).filter(|&i| a <= v[i] && v[i] < b).map(|i| w[i]).sum::<i64>();
+                assert_eq!(wm.sum(l..r, a..b).0, expect);
+                assert_eq!(wm.sum_less(l..r, u64::MAX).0, w[l..r].iter().sum::<i64>());
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ran.start <= ran.end")]
+    fn inverted_range_test() {
+        let wm = WaveletMatrix::new(&[3, 1, 4, 1, 5]);
+        #[allow(clippy::reversed_empty_ranges)]
+        wm.kth_largest(3..2, 0);
+    }
+}
@@ -1,27 +1,61 @@
 use std::collections::BinaryHeap;
-use std::cmp::Reverse;
 
 use super::Graph;
+use crate::data_structures::heap::PriorityQueue;
 
 pub fn dijkstra<'a, G, W, EW>(g:&'a G, zero: W, inf: W, s: usize, weight: EW) -> Vec<(W, Option<usize>)>
 where
     W: Clone + std::ops::Add<Output=W> + Ord,
     G: Graph<'a>,
     EW: Fn(&G::Edge) -> W,
+{
+    dijkstra_with(g, zero, inf, s, weight, BinaryHeap::new())
+}
+
+pub fn dijkstra_with<'a, G, W, EW, Q>(g:&'a G, zero: W, inf: W, s: usize, weight: EW, mut que: Q) -> Vec<(W, Option<usize>)>
+where
+    W: Clone + std::ops::Add<Output=W> + Ord,
+    G: Graph<'a>,
+    EW: Fn(&G::Edge) -> W,
+    Q: PriorityQueue<W, usize>,
 {
     let mut dist = vec![(inf, None); g.vertices()];
-    let mut que = BinaryHeap::new();
     dist[s] = (zero, None);
-    que.push(Reverse((dist[s].0.clone(), s)));
-    while let Some(Reverse((w, v))) = que.pop() {
+    que.push(dist[s].0.clone(), s);
+    while let Some((w, v)) = que.pop() {
         if dist[v].0 < w { continue }
         for (t, e) in g.delta(&v) {
             let cost = dist[v].0.clone() + weight(e);
             if dist[*t].0 > cost {
                 dist[*t] = (cost, Some(v));
-                que.push(Reverse((dist[*t].0.clone(), *t)));
+                que.push(dist[*t].0.clone(), *t);
             }
         }
     }
     dist
 }
+
+#[cfg(test)]
+mod dijkstra_test {
+    use crate::graph::dijkstra::{ dijkstra, dijkstra_with };
+    use crate::graph::directed_graph::DirectedGraph;
+    use crate::data_structures::heap::radix_heap::RadixHeap;
+    use crate::data_structures::heap::bucket_queue::BucketQueue;
+    use crate::random::{ Random, Xorshift128 };
+
+    #[test]
+    fn backends_test() {
+        let mut rng = Xorshift128::new(400);
+        let n = 200;
+        let g = DirectedGraph::from_iter(n, (0..1000).map(|_| {
+            let f = (rng.rand_u64() % n as u64) as usize;
+            let t = (rng.rand_u64() % n as u64) as usize;
+            (f, t, rng.rand_u64() % 10)
+        }));
+        let dist = |d: Vec<(u64, Option<usize>)>| d.into_iter().map(|e| e.0).collect::<Vec<_>>();
+        let expected = dist(dijkstra(&g, 0, u64::MAX, 0, |&w| w));
+        assert_eq!(dist(dijkstra_with(&g, 0, u64::MAX, 0, |&w| w, RadixHeap::new())), expected);
+        assert_eq!(dist(dijkstra_with(&g, 0, u64::MAX, 0, |&w| w, BucketQueue::new(9))), expected);
+        assert!(expected.iter().filter(|&&d| d < u64::MAX).count() > 1);
+    }
+}
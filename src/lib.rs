@@ -1,6 +1,7 @@
 #[macro_use]
 pub mod input;
 
+#[macro_use]
 pub mod algebra;
 
 #[macro_use]